@@ -0,0 +1,89 @@
+// Criterion benchmarks of every day's parse, part a and part b, on the puzzle inputs.
+// Run a single day with e.g. `cargo bench --bench days -- day9/`.
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use std::time::{Duration, Instant};
+use AdventOfCode2018::utils::{self, InputSource};
+use AdventOfCode2018::{Answer, Input, Part, Registry, Result, Solver};
+
+// Days with a step slower than this take fewer samples, so a full run stays within minutes.
+const SLOW: Duration = Duration::from_millis(20);
+const SLOW_SAMPLES: usize = 10;
+
+fn bench_days(c: &mut Criterion) {
+    let registry = Registry::with_all_days();
+    let source = InputSource::Dir(utils::inputs_dir(None));
+
+    for day in registry.days() {
+        let input = match utils::read_input(&source, day) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("Skipping day {}: {}", day, e);
+                continue;
+            }
+        };
+
+        let new_solver = || registry.get(day).unwrap();
+        let parsed_solver = || {
+            let mut solver = new_solver();
+            solver.parse(&input).unwrap();
+            solver
+        };
+
+        // Run the day once up front, to leave out unsolved parts and size the samples.
+        let (parts, slowest) = match check_day(new_solver(), &input) {
+            Ok(checked) => checked,
+            Err(e) => {
+                eprintln!("Skipping day {}: {}", day, e);
+                continue;
+            }
+        };
+
+        let mut group = c.benchmark_group(format!("day{}", day));
+        if slowest > SLOW {
+            group.sample_size(SLOW_SAMPLES);
+        }
+
+        // Every iteration gets a new solver, so nothing cached by an earlier run is reused.
+        group.bench_function("parse", |b| {
+            b.iter_batched(
+                new_solver,
+                |mut solver| {
+                    solver.parse(&input).unwrap();
+                    solver
+                },
+                BatchSize::LargeInput,
+            )
+        });
+        for part in parts {
+            group.bench_function(format!("part_{}", part), |b| {
+                b.iter_batched(
+                    parsed_solver,
+                    |mut solver| solver.run(part, &input).unwrap(),
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+        group.finish();
+    }
+}
+
+// The solved parts of a day, and the slowest of its steps.
+fn check_day(mut solver: Box<dyn Solver>, input: &Input) -> Result<(Vec<Part>, Duration)> {
+    let start = Instant::now();
+    solver.parse(input)?;
+    let mut slowest = start.elapsed();
+
+    let mut parts = Vec::new();
+    for part in Part::all() {
+        let start = Instant::now();
+        let answer = solver.run(part, input)?;
+        slowest = slowest.max(start.elapsed());
+        if answer != Answer::Unsolved {
+            parts.push(part);
+        }
+    }
+    Ok((parts, slowest))
+}
+
+criterion_group!(benches, bench_days);
+criterion_main!(benches);
@@ -0,0 +1,59 @@
+use std::fmt;
+
+// The result of a single part of a day's puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    Coord(i64, i64),
+    // Multi-line output that has to be read by a human, e.g. letters spelled out in the sky.
+    Art(String),
+    Unsolved,
+}
+
+impl Answer {
+    pub fn is_multiline(&self) -> bool {
+        match self {
+            Answer::Art(art) => art.contains('\n'),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Coord(x, y) => write!(f, "{},{}", x, y),
+            Answer::Art(art) => write!(f, "{}", art),
+            Answer::Unsolved => write!(f, "unsolved"),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Int(n as i64)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(i32, i64, isize, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_owned())
+    }
+}
@@ -0,0 +1,253 @@
+use crate::timings::{self, Timings};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use AdventOfCode2018::solver::Part;
+
+pub const DEFAULT_THRESHOLD: f64 = 10.0;
+
+// The phases of a day that get benchmarked, with the path to them in its `Timings`.
+pub const PHASES: [(&str, &[&str]); 4] = [
+    (timings::IO, &[timings::IO]),
+    (timings::PARSE, &[timings::CPU, timings::PARSE]),
+    (
+        timings::part(Part::A),
+        &[timings::CPU, timings::part(Part::A)],
+    ),
+    (
+        timings::part(Part::B),
+        &[timings::CPU, timings::part(Part::B)],
+    ),
+];
+
+#[derive(Copy, Clone, Debug)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let n = sorted.len();
+        // The middle sample, or the mean of the middle two.
+        let median = (sorted[(n - 1) / 2] + sorted[n / 2]) / 2;
+
+        let secs: Vec<f64> = sorted.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+// The timings of every run of a single day, by phase.
+pub struct Samples {
+    pub day: u32,
+    phases: BTreeMap<&'static str, Vec<Duration>>,
+}
+
+impl Samples {
+    pub fn new(day: u32) -> Self {
+        Samples {
+            day,
+            phases: BTreeMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, timings: &Timings) {
+        for (name, path) in PHASES.iter() {
+            if let Some(duration) = timings.duration_of(path) {
+                self.phases.entry(name).or_default().push(duration);
+            }
+        }
+    }
+
+    // Stats for each phase that ran, in the order of `PHASES`.
+    pub fn stats(&self) -> Vec<(&'static str, Stats)> {
+        PHASES
+            .iter()
+            .filter_map(|(name, _)| {
+                let samples = self.phases.get(name)?;
+                Some((*name, Stats::from_samples(samples)))
+            })
+            .collect()
+    }
+}
+
+// Median timings from an earlier benchmark, keyed by day and phase, e.g.
+//
+//   [day14]
+//   "part a" = 30636908
+//
+// Durations are stored in nanoseconds.
+#[derive(Default)]
+pub struct Baseline {
+    medians: BTreeMap<(u32, String), Duration>,
+}
+
+impl Baseline {
+    pub fn load(path: &str) -> Result<Baseline, String> {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+        Baseline::parse(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn parse(text: &str) -> Result<Baseline, String> {
+        let table: toml::value::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+        let mut medians = BTreeMap::new();
+
+        for (key, phases) in &table {
+            let day: u32 = key
+                .strip_prefix("day")
+                .and_then(|d| d.parse().ok())
+                .ok_or_else(|| format!("invalid day `{}`", key))?;
+            let phases = phases
+                .as_table()
+                .ok_or_else(|| format!("`{}` should be a table", key))?;
+
+            for (phase, value) in phases {
+                let nanos = value
+                    .as_integer()
+                    .filter(|&n| n >= 0)
+                    .ok_or_else(|| format!("`{}.{}` should be a duration in ns", key, phase))?;
+                medians.insert((day, phase.clone()), Duration::from_nanos(nanos as u64));
+            }
+        }
+
+        Ok(Baseline { medians })
+    }
+
+    pub fn from_samples(samples: &[Samples]) -> Baseline {
+        let mut baseline = Baseline::default();
+        for day in samples {
+            for (phase, stats) in day.stats() {
+                baseline
+                    .medians
+                    .insert((day.day, phase.to_string()), stats.median);
+            }
+        }
+        baseline
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let mut table = toml::value::Table::new();
+        for ((day, phase), median) in &self.medians {
+            let day = table
+                .entry(format!("day{}", day))
+                .or_insert_with(|| toml::Value::Table(toml::value::Table::new()));
+            if let toml::Value::Table(day) = day {
+                day.insert(
+                    phase.clone(),
+                    toml::Value::Integer(median.as_nanos() as i64),
+                );
+            }
+        }
+
+        let text = toml::to_string(&toml::Value::Table(table)).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| format!("failed to write {}: {}", path, e))
+    }
+
+    pub fn get(&self, day: u32, phase: &str) -> Option<Duration> {
+        self.medians.get(&(day, phase.to_string())).cloned()
+    }
+}
+
+// How much slower `median` is than `baseline`, as a percentage.
+pub fn change(median: Duration, baseline: Duration) -> f64 {
+    let baseline = baseline.as_secs_f64();
+    if baseline == 0.0 {
+        return 0.0;
+    }
+    (median.as_secs_f64() - baseline) / baseline * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: &[u64]) -> Vec<Duration> {
+        ms.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    fn close(a: Duration, b: f64) -> bool {
+        (a.as_secs_f64() * 1000.0 - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn stats_of_odd_and_even_samples() {
+        let odd = Stats::from_samples(&ms(&[3, 1, 2]));
+        assert_eq!(odd.min, Duration::from_millis(1));
+        assert_eq!(odd.median, Duration::from_millis(2));
+        assert!(close(odd.mean, 2.0));
+        assert!(close(odd.stddev, 1.0));
+
+        let even = Stats::from_samples(&ms(&[4, 1, 3, 2]));
+        assert_eq!(even.median, Duration::from_micros(2500));
+        assert!(close(even.mean, 2.5));
+        // The sample standard deviation, divided by n - 1.
+        assert!(close(even.stddev, (5.0f64 / 3.0).sqrt()));
+
+        let single = Stats::from_samples(&ms(&[7]));
+        assert_eq!(single.median, Duration::from_millis(7));
+        assert_eq!(single.stddev, Duration::default());
+    }
+
+    #[test]
+    fn baseline_from_samples_round_trips() {
+        let mut samples = Samples::new(14);
+        for part_a in [30, 10, 20] {
+            let mut cpu = Timings::new(timings::CPU);
+            cpu.push(Timings::leaf("part a", Duration::from_millis(part_a)));
+            let mut day = Timings::new("Day 14");
+            day.push(cpu);
+            samples.add(&day);
+        }
+
+        let baseline = Baseline::from_samples(&[samples]);
+        assert_eq!(baseline.get(14, "part a"), Some(Duration::from_millis(20)));
+        assert_eq!(baseline.get(14, "part b"), None);
+
+        let path = std::env::temp_dir().join(format!("baseline-{}.toml", std::process::id()));
+        let path = path.to_str().unwrap();
+        baseline.save(path).unwrap();
+        let loaded = Baseline::load(path);
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(
+            loaded.unwrap().get(14, "part a"),
+            Some(Duration::from_millis(20))
+        );
+    }
+
+    #[test]
+    fn baseline_parse_errors() {
+        let error = |text| Baseline::parse(text).err().unwrap();
+        assert_eq!(error("[week1]\nio = 5"), "invalid day `week1`");
+        assert_eq!(error("day1 = 5"), "`day1` should be a table");
+        assert_eq!(
+            error("[day1]\nio = -5"),
+            "`day1.io` should be a duration in ns"
+        );
+    }
+
+    #[test]
+    fn regressions_past_the_threshold() {
+        let baseline = Duration::from_millis(100);
+        assert_eq!(change(Duration::from_millis(50), baseline), -50.0);
+        assert!(change(Duration::from_millis(110), baseline) <= DEFAULT_THRESHOLD);
+        assert!(change(Duration::from_millis(111), baseline) > DEFAULT_THRESHOLD);
+        assert_eq!(change(baseline, Duration::default()), 0.0);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::error::{Error, Result};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// Lets the runner stop a day that's out of time. Threads can't be killed, so solvers call
+// `check` from their long-running loops, and give up once their thread's flag is raised.
+
+thread_local! {
+    static FLAG: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+// Solvers running on this thread stop at their next `check` once `flag` is set.
+pub fn install(flag: Arc<AtomicBool>) {
+    FLAG.with(|f| *f.borrow_mut() = Some(flag));
+}
+
+// An error once this thread has been cancelled. Without a flag, it never is.
+pub fn check() -> Result<()> {
+    let cancelled = FLAG.with(|f| {
+        f.borrow()
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    });
+    if cancelled {
+        Err(Error::new("cancelled"))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn only_the_flagged_thread_is_cancelled() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let worker = {
+            let flag = Arc::clone(&flag);
+            thread::spawn(move || {
+                install(Arc::clone(&flag));
+                let before = check();
+                flag.store(true, Ordering::Relaxed);
+                (before, check())
+            })
+        };
+
+        let (before, after) = worker.join().unwrap();
+        assert_eq!(before, Ok(()));
+        assert_eq!(after, Err(Error::new("cancelled")));
+        assert_eq!(check(), Ok(()));
+    }
+}
@@ -0,0 +1,333 @@
+use crate::bench;
+use crate::report::Format;
+use std::time::Duration;
+use AdventOfCode2018::solver::Part;
+
+pub const USAGE: &str = "\
+Usage: AdventOfCode2018 [OPTIONS] [DAYS...]
+
+Options:
+  -d, --day <DAYS>    Days to run, e.g. `15`, `12-18` or `1,3,5-7` (default: all)
+  -p, --part <PART>   Only run part `a` or part `b` of each day
+  -l, --list          List the available days
+  -i, --input <PATH>  Read the input for a single day from PATH, or stdin for `-`
+      --inputs-dir <DIR>
+                      Directory holding Day{N}.txt inputs (default: $AOC_INPUTS or inputs/)
+  -f, --format <FORMAT>
+                      Output format: `text`, `json`, `csv` or `markdown` (default: text)
+  -j, --jobs <N>      Run up to N days at the same time (default: 1)
+  -t, --timeout <SECS>
+                      Give up on a day that runs for longer than SECS seconds
+      --verify        Check the answers against answers.toml
+      --bench <N>     Time N runs of each day after a warm-up run
+      --baseline <FILE>
+                      Compare the benchmark against a saved baseline
+      --save-baseline <FILE>
+                      Save the benchmark as a baseline
+      --threshold <PCT>
+                      Slowdown that counts as a regression (default: 10)
+      --disassemble   Print a day's emulator program as pseudo-code
+      --dot           With --disassemble, print the control-flow graph for Graphviz
+      --debug         Step through a day's emulator program, reading commands from stdin
+  -h, --help          Print this message";
+
+pub struct Options {
+    // Sorted and deduplicated. Empty means every available day.
+    pub days: Vec<u32>,
+    pub parts: Vec<Part>,
+    pub list: bool,
+    pub input: Option<String>,
+    pub inputs_dir: Option<String>,
+    pub format: Format,
+    pub jobs: usize,
+    pub timeout: Option<Duration>,
+    pub verify: bool,
+    pub bench: Option<usize>,
+    pub baseline: Option<String>,
+    pub save_baseline: Option<String>,
+    // Percentage slowdown against the baseline that counts as a regression.
+    pub threshold: f64,
+    pub disassemble: bool,
+    pub dot: bool,
+    pub debug: bool,
+    pub help: bool,
+}
+
+impl Options {
+    pub fn parse<I>(args: I) -> Result<Options, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut options = Options {
+            days: Vec::new(),
+            parts: Part::all(),
+            list: false,
+            input: None,
+            inputs_dir: None,
+            format: Format::Text,
+            jobs: 1,
+            timeout: None,
+            verify: false,
+            bench: None,
+            baseline: None,
+            save_baseline: None,
+            threshold: bench::DEFAULT_THRESHOLD,
+            disassemble: false,
+            dot: false,
+            debug: false,
+            help: false,
+        };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-d" | "--day" => {
+                    let spec = args.next().ok_or("missing value for --day")?;
+                    options.days.extend(parse_days(&spec)?);
+                }
+                "-p" | "--part" => {
+                    let part = args.next().ok_or("missing value for --part")?;
+                    options.parts = vec![parse_part(&part)?];
+                }
+                "-l" | "--list" => options.list = true,
+                "-i" | "--input" => {
+                    let path = args.next().ok_or("missing value for --input")?;
+                    options.input = Some(path);
+                }
+                "--inputs-dir" => {
+                    let dir = args.next().ok_or("missing value for --inputs-dir")?;
+                    options.inputs_dir = Some(dir);
+                }
+                "-f" | "--format" => {
+                    let format = args.next().ok_or("missing value for --format")?;
+                    options.format = format.parse()?;
+                }
+                "-j" | "--jobs" => {
+                    let jobs = args.next().ok_or("missing value for --jobs")?;
+                    options.jobs = parse_count(&jobs, "jobs")?;
+                }
+                "-t" | "--timeout" => {
+                    let secs = args.next().ok_or("missing value for --timeout")?;
+                    options.timeout = Some(parse_timeout(&secs)?);
+                }
+                "--verify" => options.verify = true,
+                "--bench" => {
+                    let runs = args.next().ok_or("missing value for --bench")?;
+                    options.bench = Some(parse_count(&runs, "runs")?);
+                }
+                "--baseline" => {
+                    let path = args.next().ok_or("missing value for --baseline")?;
+                    options.baseline = Some(path);
+                }
+                "--save-baseline" => {
+                    let path = args.next().ok_or("missing value for --save-baseline")?;
+                    options.save_baseline = Some(path);
+                }
+                "--threshold" => {
+                    let pct = args.next().ok_or("missing value for --threshold")?;
+                    options.threshold = pct
+                        .parse()
+                        .ok()
+                        .filter(|&pct: &f64| pct >= 0.0)
+                        .ok_or_else(|| format!("invalid threshold `{}`", pct))?;
+                }
+                "--disassemble" => options.disassemble = true,
+                "--dot" => options.dot = true,
+                "--debug" => options.debug = true,
+                "-h" | "--help" => options.help = true,
+                _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+                _ => options.days.extend(parse_days(&arg)?),
+            }
+        }
+
+        if options.bench.is_none()
+            && (options.baseline.is_some() || options.save_baseline.is_some())
+        {
+            return Err("--baseline and --save-baseline require --bench".to_string());
+        }
+        if options.bench.is_some() && options.verify {
+            return Err("--bench and --verify can't be used together".to_string());
+        }
+        // Days running side by side would skew each other's timings.
+        if options.bench.is_some() && options.jobs > 1 {
+            return Err("--bench can't be used with --jobs".to_string());
+        }
+        if options.bench.is_some() && options.format != Format::Text {
+            return Err("--bench only supports the text format".to_string());
+        }
+        // Benchmarks run each day to completion, so a timeout would never be enforced.
+        if options.bench.is_some() && options.timeout.is_some() {
+            return Err("--bench can't be used with --timeout".to_string());
+        }
+
+        options.days.sort_unstable();
+        options.days.dedup();
+
+        if options.dot && !options.disassemble {
+            return Err("--dot requires --disassemble".to_string());
+        }
+        if options.disassemble && options.days.len() != 1 {
+            return Err("--disassemble needs exactly one day".to_string());
+        }
+        if options.debug && options.days.len() != 1 {
+            return Err("--debug needs exactly one day".to_string());
+        }
+        if options.debug && options.disassemble {
+            return Err("--debug and --disassemble can't be used together".to_string());
+        }
+
+        if options.input.is_some() && options.days.len() != 1 {
+            return Err("--input needs exactly one day".to_string());
+        }
+        // The accepted answers belong to the inputs next to them, not to some other input.
+        if options.input.is_some() && options.verify {
+            return Err("--verify can't be used with --input".to_string());
+        }
+        // Stdin can only be read once.
+        if options.input.as_deref() == Some("-") && options.bench.is_some() {
+            return Err("--bench can't read the input from stdin".to_string());
+        }
+        if options.input.as_deref() == Some("-") && options.debug {
+            return Err(
+                "--debug reads its commands from stdin, so it can't read the input too".to_string(),
+            );
+        }
+
+        Ok(options)
+    }
+}
+
+// Parses a comma separated list of days and inclusive day ranges, e.g. `1,3,12-18`.
+fn parse_days(spec: &str) -> Result<Vec<u32>, String> {
+    let mut days = Vec::new();
+
+    for item in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        match item.find('-') {
+            Some(idx) => {
+                let from = parse_day(&item[..idx])?;
+                let to = parse_day(&item[idx + 1..])?;
+                if from > to {
+                    return Err(format!("invalid day range `{}`", item));
+                }
+                days.extend(from..=to);
+            }
+            None => days.push(parse_day(item)?),
+        }
+    }
+
+    if days.is_empty() {
+        return Err(format!("invalid day selection `{}`", spec));
+    }
+
+    Ok(days)
+}
+
+// Advent of Code runs from the 1st to the 25th of December.
+fn parse_day(s: &str) -> Result<u32, String> {
+    match s.trim().parse() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(format!("invalid day `{}`", s)),
+    }
+}
+
+fn parse_count(s: &str, what: &str) -> Result<usize, String> {
+    match s.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("invalid number of {} `{}`", what, s)),
+    }
+}
+
+fn parse_timeout(s: &str) -> Result<Duration, String> {
+    match s.parse::<f64>() {
+        Ok(secs) if secs > 0.0 && secs.is_finite() => Ok(Duration::from_secs_f64(secs)),
+        _ => Err(format!("invalid timeout `{}`", s)),
+    }
+}
+
+fn parse_part(s: &str) -> Result<Part, String> {
+    match s.to_lowercase().as_str() {
+        "a" | "1" => Ok(Part::A),
+        "b" | "2" => Ok(Part::B),
+        _ => Err(format!("invalid part `{}`, expected `a` or `b`", s)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    fn error(args: &[&str]) -> String {
+        parse(args).err().unwrap()
+    }
+
+    #[test]
+    fn days_and_ranges() {
+        assert_eq!(parse_days("15"), Ok(vec![15]));
+        assert_eq!(parse_days("1,3, 5-7"), Ok(vec![1, 3, 5, 6, 7]));
+        assert_eq!(parse_days("25-25"), Ok(vec![25]));
+        assert_eq!(parse_days("18-12"), Err("invalid day range `18-12`".into()));
+        assert_eq!(parse_days("0"), Err("invalid day `0`".into()));
+        assert_eq!(parse_days("24-26"), Err("invalid day `26`".into()));
+        assert_eq!(parse_days("x"), Err("invalid day `x`".into()));
+        assert_eq!(parse_days(","), Err("invalid day selection `,`".into()));
+    }
+
+    #[test]
+    fn days_are_sorted_and_deduplicated() {
+        let options = parse(&["7", "-d", "3-5", "--day", "4,7"]).unwrap();
+        assert_eq!(options.days, [3, 4, 5, 7]);
+        assert!(parse(&[]).unwrap().days.is_empty());
+    }
+
+    #[test]
+    fn options_that_need_exactly_one_day() {
+        for option in ["--disassemble", "--debug"] {
+            assert!(parse(&[option, "19"]).is_ok());
+            assert!(parse(&[option, "19", "19"]).is_ok());
+            let message = format!("{} needs exactly one day", option);
+            assert_eq!(error(&[option]), message);
+            assert_eq!(error(&[option, "19,21"]), message);
+        }
+
+        assert!(parse(&["-i", "day19.txt", "19"]).is_ok());
+        assert_eq!(error(&["-i", "day19.txt"]), "--input needs exactly one day");
+        assert_eq!(
+            error(&["-i", "day19.txt", "1-2"]),
+            "--input needs exactly one day"
+        );
+    }
+
+    #[test]
+    fn conflicting_options() {
+        assert_eq!(error(&["--dot", "19"]), "--dot requires --disassemble");
+        assert_eq!(
+            error(&["--verify", "-i", "day14.txt", "14"]),
+            "--verify can't be used with --input"
+        );
+        assert_eq!(
+            error(&["--debug", "--disassemble", "19"]),
+            "--debug and --disassemble can't be used together"
+        );
+        assert_eq!(
+            error(&["--debug", "-i", "-", "19"]),
+            "--debug reads its commands from stdin, so it can't read the input too"
+        );
+        assert_eq!(
+            error(&["--bench", "3", "-i", "-", "19"]),
+            "--bench can't read the input from stdin"
+        );
+        assert_eq!(
+            error(&["--bench", "3", "-t", "5", "19"]),
+            "--bench can't be used with --timeout"
+        );
+        assert_eq!(
+            error(&["--baseline", "b.toml"]),
+            "--baseline and --save-baseline require --bench"
+        );
+        assert_eq!(error(&["--frobnicate"]), "unknown option `--frobnicate`");
+    }
+}
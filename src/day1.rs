@@ -1,43 +1,113 @@
-use crate::utils;
+use crate::answer::Answer;
+use crate::cancel;
+use crate::error::{Error, LineContext, Result};
+use crate::solver::Solver;
+use crate::utils::{self, Input};
 use std::collections::HashSet;
 
-pub fn day1(lines: &mut Vec<String>) {
-    println!("Running Day 1 - a");
+#[derive(Default)]
+pub struct Day1 {
+    changes: Vec<i32>,
+}
 
-    let mut day1a: i32 = 0;
+impl Solver for Day1 {
+    fn day(&self) -> u32 {
+        1
+    }
 
-    let mut frequencies: HashSet<i32> = HashSet::new();
-    let mut day1b: i32 = 0;
-    let mut day1bComplete: bool = false;
-    let mut day1aComplete: bool = false;
+    fn title(&self) -> &'static str {
+        "Chronal Calibration"
+    }
 
-    while !day1aComplete || !day1bComplete {
-        for line in lines.iter() {
-            let sign = &line[..1];
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        self.changes = parse_changes(input.lines())?;
+        Ok(())
+    }
 
-            let mult = match sign {
-                "-" => -1,
-                "+" => 1,
-                _ => return,
-            };
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(self.changes.iter().sum::<i32>().into())
+    }
 
-            let val = &line[1..];
-            let val = utils::parse::<i32>(val);
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        let mut frequency: i32 = 0;
+        let mut frequencies: HashSet<i32> = HashSet::new();
+        frequencies.insert(frequency);
 
-            day1a += mult * val;
+        // Changes that never add up to a frequency seen before would go round forever.
+        for change in self.changes.iter().cycle() {
+            cancel::check()?;
+            frequency += change;
 
-            if !day1bComplete && !frequencies.insert(day1a) {
-                day1b = day1a;
-                day1bComplete = true;
+            if !frequencies.insert(frequency) {
+                break;
             }
         }
 
-        if !day1aComplete {
-            day1aComplete = true;
-            println!("Total = {}", day1a);
-        }
+        Ok(frequency.into())
     }
+}
 
-    println!("Running Day 1 - b");
-    println!("First Frequency Reached Twice = {}", day1b);
+fn parse_changes(lines: &[String]) -> Result<Vec<i32>> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            let mult = match line.get(..1) {
+                Some("-") => -1,
+                Some("+") => 1,
+                _ => {
+                    return Err(Error::at_line(
+                        idx,
+                        format!("expected a signed change, got `{}`", line),
+                    ))
+                }
+            };
+
+            let val = &line[1..];
+            let val = utils::parse::<i32>(val).line(idx)?;
+
+            Ok(mult * val)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    #[test]
+    fn part_a_examples() {
+        assert_eq!(
+            run_example::<Day1>(Part::A, &["+1", "-2", "+3", "+1"]),
+            3.into()
+        );
+        assert_eq!(run_example::<Day1>(Part::A, &["+1", "+1", "+1"]), 3.into());
+        assert_eq!(run_example::<Day1>(Part::A, &["+1", "+1", "-2"]), 0.into());
+        assert_eq!(
+            run_example::<Day1>(Part::A, &["-1", "-2", "-3"]),
+            (-6).into()
+        );
+    }
+
+    #[test]
+    fn part_b_examples() {
+        assert_eq!(
+            run_example::<Day1>(Part::B, &["+1", "-2", "+3", "+1"]),
+            2.into()
+        );
+        assert_eq!(run_example::<Day1>(Part::B, &["+1", "-1"]), 0.into());
+        assert_eq!(
+            run_example::<Day1>(Part::B, &["+3", "+3", "+4", "-2", "-4"]),
+            10.into()
+        );
+        assert_eq!(
+            run_example::<Day1>(Part::B, &["-6", "+3", "+8", "+5", "-6"]),
+            5.into()
+        );
+        assert_eq!(
+            run_example::<Day1>(Part::B, &["+7", "+7", "-2", "-7", "-4"]),
+            14.into()
+        );
+    }
 }
@@ -1,88 +1,133 @@
-use crate::utils;
-use regex::Regex;
+use crate::answer::Answer;
+use crate::error::{Error, LineContext, Result};
+use crate::solver::Solver;
+use crate::utils::{self, Input, Vec2};
 use std::collections::HashSet;
 
-pub fn day10(lines: &mut Vec<String>) {
-    println!("Running Day 10 - a");
+#[derive(Default)]
+pub struct Day10 {
+    stars: Vec<Star>,
+    seconds: Option<usize>,
+}
+
+impl Solver for Day10 {
+    fn day(&self) -> u32 {
+        10
+    }
+
+    fn title(&self) -> &'static str {
+        "The Stars Align"
+    }
+
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        self.stars = parse_stars(input.lines())?;
+        if self.stars.is_empty() {
+            return Err(Error::new("no stars in the sky"));
+        }
+        Ok(())
+    }
+
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        self.align();
+        Ok(Answer::Art(render_sky(&self.stars)))
+    }
 
-    let rex = Regex::new(r"\w+=< *(-?\d+), *(-?\d+)> \w+=< *(-?\d+), *(-?\d+)>").unwrap();
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(self.align().into())
+    }
+}
+
+impl Day10 {
+    // The stars move in place, so they're only aligned once, by whichever part runs first.
+    fn align(&mut self) -> usize {
+        let stars = &mut self.stars;
+        *self.seconds.get_or_insert_with(|| align_stars(stars))
+    }
+}
 
+fn parse_stars(lines: &[String]) -> Result<Vec<Star>> {
     let mut stars: Vec<Star> = Vec::new();
 
-    for line in lines {
-        let caps = rex.captures(line).unwrap();
-        stars.push(Star {
-            position: (utils::parse(&caps[1]), utils::parse(&caps[2])),
-            velocity: (utils::parse(&caps[3]), utils::parse(&caps[4])),
-        });
+    for (idx, line) in lines.iter().enumerate() {
+        match utils::ints(line).line(idx)?[..] {
+            [px, py, vx, vy] => stars.push(Star {
+                position: Vec2::new(px, py),
+                velocity: Vec2::new(vx, vy),
+            }),
+            _ => return Err(Error::at_line(idx, format!("invalid star `{}`", line))),
+        }
     }
 
+    Ok(stars)
+}
+
+// Advances the sky until its area stops shrinking.
+// Returns the seconds it took to align the stars.
+fn align_stars(stars: &mut [Star]) -> usize {
     let mut min_area = 0x7FFFFFFFFFFFFFFF;
     let mut seconds = 0;
 
     loop {
-        advance_sky(&mut stars);
-        let sky_area = get_area(&stars);
+        advance_sky(stars);
+        let sky_area = get_area(stars);
         if sky_area < min_area {
             min_area = sky_area;
             seconds += 1;
         } else {
-            reverse_sky(&mut stars);
-            print_sky(&stars);
+            reverse_sky(stars);
             break;
         }
     }
 
-    println!("Running Day 10 - b");
-
-    println!("Seconds = {}", seconds);
+    seconds
 }
 
-fn advance_sky(stars: &mut Vec<Star>) {
+fn advance_sky(stars: &mut [Star]) {
     for star in stars {
-        star.position.0 += star.velocity.0;
-        star.position.1 += star.velocity.1;
+        star.position += star.velocity;
     }
 }
 
-fn reverse_sky(stars: &mut Vec<Star>) {
+fn reverse_sky(stars: &mut [Star]) {
     for star in stars {
-        star.position.0 -= star.velocity.0;
-        star.position.1 -= star.velocity.1;
+        star.position -= star.velocity;
     }
 }
 
-fn print_sky(stars: &Vec<Star>) {
-    let bounds = get_bounds(&stars);
+fn render_sky(stars: &[Star]) -> String {
+    let bounds = get_bounds(stars);
 
-    let mut sky: HashSet<(i32, i32)> = HashSet::new();
+    let mut sky: HashSet<Vec2<i32>> = HashSet::new();
 
     for star in stars {
-        sky.insert((star.position.0 - bounds.0, star.position.1 - bounds.1));
-    }
-
-    for j in 0..bounds.3 + 1 {
-        let mut line: Vec<char> = vec!['.'; (bounds.2 + 1) as usize];
-        for i in 0..bounds.2 + 1 {
-            if sky.contains(&(i, j)) {
-                line[i as usize] = '#';
-            }
-        }
-        println!("{}", line.iter().collect::<String>());
+        sky.insert(star.position - Vec2::new(bounds.0, bounds.1));
     }
 
-    println!("");
+    (0..bounds.3 + 1)
+        .map(|j| {
+            (0..bounds.2 + 1)
+                .map(|i| {
+                    if sky.contains(&Vec2::new(i, j)) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
 }
 
-fn get_bounds(stars: &Vec<Star>) -> (i32, i32, i32, i32) {
+fn get_bounds(stars: &[Star]) -> (i32, i32, i32, i32) {
     let xBounds = (
-        stars.iter().map(|s| s.position.0).min().unwrap(),
-        stars.iter().map(|s| s.position.0).max().unwrap(),
+        stars.iter().map(|s| s.position.x).min().unwrap(),
+        stars.iter().map(|s| s.position.x).max().unwrap(),
     );
 
     let yBounds = (
-        stars.iter().map(|s| s.position.1).min().unwrap(),
-        stars.iter().map(|s| s.position.1).max().unwrap(),
+        stars.iter().map(|s| s.position.y).min().unwrap(),
+        stars.iter().map(|s| s.position.y).max().unwrap(),
     );
 
     (
@@ -93,12 +138,75 @@ fn get_bounds(stars: &Vec<Star>) -> (i32, i32, i32, i32) {
     )
 }
 
-fn get_area(stars: &Vec<Star>) -> i64 {
+fn get_area(stars: &[Star]) -> i64 {
     let bounds = get_bounds(stars);
     bounds.2 as i64 * bounds.3 as i64
 }
 
 struct Star {
-    position: (i32, i32),
-    velocity: (i32, i32),
+    position: Vec2<i32>,
+    velocity: Vec2<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    const STARS: [&str; 31] = [
+        "position=< 9,  1> velocity=< 0,  2>",
+        "position=< 7,  0> velocity=<-1,  0>",
+        "position=< 3, -2> velocity=<-1,  1>",
+        "position=< 6, 10> velocity=<-2, -1>",
+        "position=< 2, -4> velocity=< 2,  2>",
+        "position=<-6, 10> velocity=< 2, -2>",
+        "position=< 1,  8> velocity=< 1, -1>",
+        "position=< 1,  7> velocity=< 1,  0>",
+        "position=<-3, 11> velocity=< 1, -2>",
+        "position=< 7,  6> velocity=<-1, -1>",
+        "position=<-2,  3> velocity=< 1,  0>",
+        "position=<-4,  3> velocity=< 2,  0>",
+        "position=<10, -3> velocity=<-1,  1>",
+        "position=< 5, 11> velocity=< 1, -2>",
+        "position=< 4,  7> velocity=< 0, -1>",
+        "position=< 8, -2> velocity=< 0,  1>",
+        "position=<15,  0> velocity=<-2,  0>",
+        "position=< 1,  6> velocity=< 1,  0>",
+        "position=< 8,  9> velocity=< 0, -1>",
+        "position=< 3,  3> velocity=<-1,  1>",
+        "position=< 0,  5> velocity=< 0, -1>",
+        "position=<-2,  2> velocity=< 2,  0>",
+        "position=< 5, -2> velocity=< 1,  2>",
+        "position=< 1,  4> velocity=< 2,  1>",
+        "position=<-2,  7> velocity=< 2, -2>",
+        "position=< 3,  6> velocity=<-1, -1>",
+        "position=< 5,  0> velocity=< 1,  0>",
+        "position=<-6,  0> velocity=< 2,  0>",
+        "position=< 5,  9> velocity=< 1, -2>",
+        "position=<14,  7> velocity=<-2,  0>",
+        "position=<-3,  6> velocity=< 2, -1>",
+    ];
+
+    #[test]
+    fn part_a_example() {
+        let hi = [
+            "#...#..###",
+            "#...#...#.",
+            "#...#...#.",
+            "#####...#.",
+            "#...#...#.",
+            "#...#...#.",
+            "#...#...#.",
+            "#...#..###",
+        ];
+        assert_eq!(
+            run_example::<Day10>(Part::A, &STARS),
+            Answer::Art(hi.join("\n"))
+        );
+    }
+
+    #[test]
+    fn part_b_example() {
+        assert_eq!(run_example::<Day10>(Part::B, &STARS), 3.into());
+    }
 }
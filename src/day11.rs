@@ -1,94 +1,110 @@
-use crate::utils;
+use crate::answer::Answer;
+use crate::error::{LineContext, Result};
+use crate::solver::Solver;
+use crate::utils::{self, Grid, Input, Point, Vec2};
+
+#[derive(Default)]
+pub struct Day11 {
+    grid: Option<Grid<i32>>,
+}
 
-pub fn day11(lines: &mut Vec<String>) {
-    println!("Running Day 11 - a");
+impl Solver for Day11 {
+    fn day(&self) -> u32 {
+        11
+    }
 
-    // let serial: i32 = 18;
-    // let serial: i32 = 42;
-    let serial: i32 = utils::parse(&lines[0]);
+    fn title(&self) -> &'static str {
+        "Chronal Charge"
+    }
 
-    let mut grid: Grid = [[0; GRID_SIZE]; GRID_SIZE];
-    for c in iproduct!(0..GRID_SIZE, 0..GRID_SIZE) {
-        grid[c.0][c.1] = calc_power(c.0 as i32, c.1 as i32, serial);
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        self.grid = Some(build_grid(input.single_line()?)?);
+        Ok(())
     }
 
-    let sums = build_sums(&grid, 3);
-    let winner = sums.find_winner();
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        let grid = self.grid.as_ref().unwrap();
 
-    println!(
-        "Largest Power 3x3 anchor = {:?}, power = {}",
-        winner.0, winner.1
-    );
+        let sums = build_sums(grid, 3);
+        let winner = sums.find_winner();
+
+        Ok(Answer::Coord(winner.0.x as i64, winner.0.y as i64))
+    }
 
-    println!("Running Day 11 - b");
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        let grid = self.grid.as_ref().unwrap();
 
-    let mut patch_size_winner: [Identifier; GRID_SIZE] = [((0, 0), 0); GRID_SIZE];
-    let mut all_sums: Vec<Sums> = Vec::with_capacity(GRID_SIZE);
+        let mut patch_size_winner: [Identifier; GRID_SIZE] = [(Vec2::zero(), 0); GRID_SIZE];
+        let mut all_sums: Vec<Sums> = Vec::with_capacity(GRID_SIZE);
 
-    all_sums.push(build_sums(&grid, 1));
-    patch_size_winner[0] = all_sums[0].find_winner();
-    all_sums.push(build_sums(&grid, 2));
-    patch_size_winner[1] = all_sums[1].find_winner();
+        all_sums.push(build_sums(grid, 1));
+        patch_size_winner[0] = all_sums[0].find_winner();
+        all_sums.push(build_sums(grid, 2));
+        patch_size_winner[1] = all_sums[1].find_winner();
 
-    for patch_size in 3..GRID_SIZE + 1 {
-        all_sums.push(iter_sums(&all_sums, patch_size));
-        patch_size_winner[patch_size - 1] = all_sums[patch_size - 1].find_winner();
+        for patch_size in 3..GRID_SIZE + 1 {
+            all_sums.push(iter_sums(&all_sums, patch_size));
+            patch_size_winner[patch_size - 1] = all_sums[patch_size - 1].find_winner();
+        }
+
+        let winner = patch_size_winner
+            .iter()
+            .enumerate()
+            .max_by(|a, b| (a.1).1.cmp(&(b.1).1))
+            .unwrap();
+
+        Ok(format!("{},{}", (winner.1).0, winner.0 + 1).into())
     }
+}
 
-    let winner = patch_size_winner
-        .iter()
-        .enumerate()
-        .max_by(|a, b| (a.1).1.cmp(&(b.1).1))
-        .unwrap();
+fn build_grid(serial: &str) -> Result<Grid<i32>> {
+    let serial: i32 = utils::parse(serial).line(0)?;
 
-    println!(
-        "Largest Power Square Identifier = {},{},{} with power = {}",
-        ((winner.1).0).0,
-        ((winner.1).0).1,
-        winner.0 + 1,
-        (winner.1).1
-    );
+    Ok(Grid::from_fn(GRID_SIZE, GRID_SIZE, |p| {
+        calc_power(p.x as i32, p.y as i32, serial)
+    }))
 }
 
 fn calc_power(x: i32, y: i32, serial: i32) -> i32 {
     ((((x + 10) * y + serial) * (x + 10)) / 100) % 10 - 5
 }
 
-fn build_sums(grid: &Grid, patch_size: usize) -> Sums {
+fn build_sums(grid: &Grid<i32>, patch_size: usize) -> Sums {
     let mut sums = Sums::new(patch_size);
 
-    for coord in iproduct!(0..sums.width, 0..sums.width) {
-        sums[coord.0][coord.1] = iproduct!(0..patch_size, 0..patch_size)
-            .map(|c| grid[coord.0 + c.0][coord.1 + c.1])
+    for coord in sums.sums.points() {
+        sums[coord] = iproduct!(0..patch_size, 0..patch_size)
+            .map(|c| grid[coord + Vec2::from(c)])
             .sum();
     }
 
     sums
 }
 
-fn iter_sums(all_sums: &Vec<Sums>, patch_size: usize) -> Sums {
-    if patch_size % 2 == 0 {
-        iter_sums_even(&all_sums, patch_size)
+fn iter_sums(all_sums: &[Sums], patch_size: usize) -> Sums {
+    if patch_size.is_multiple_of(2) {
+        iter_sums_even(all_sums, patch_size)
     } else {
-        iter_sums_odd(&all_sums, patch_size)
+        iter_sums_odd(all_sums, patch_size)
     }
 }
 
-fn iter_sums_even(all_sums: &Vec<Sums>, patch_size: usize) -> Sums {
+fn iter_sums_even(all_sums: &[Sums], patch_size: usize) -> Sums {
     let half_sums = &all_sums[patch_size / 2 - 1];
+    let half = half_sums.patch_size;
     let mut sums = Sums::new(patch_size);
 
-    for coord in iproduct!(0..sums.width, 0..sums.width) {
-        sums[coord.0][coord.1] = half_sums[coord.0][coord.1]
-            + half_sums[coord.0 + half_sums.patch_size][coord.1]
-            + half_sums[coord.0][coord.1 + half_sums.patch_size]
-            + half_sums[coord.0 + half_sums.patch_size][coord.1 + half_sums.patch_size];
+    for p in sums.sums.points() {
+        sums[p] = half_sums[p]
+            + half_sums[p + Vec2::new(half, 0)]
+            + half_sums[p + Vec2::new(0, half)]
+            + half_sums[p + Vec2::new(half, half)];
     }
 
     sums
 }
 
-fn iter_sums_odd(all_sums: &Vec<Sums>, patch_size: usize) -> Sums {
+fn iter_sums_odd(all_sums: &[Sums], patch_size: usize) -> Sums {
     let small = patch_size / 2;
     let large = patch_size / 2 + 1;
     let small_sums = &all_sums[small - 1];
@@ -96,12 +112,12 @@ fn iter_sums_odd(all_sums: &Vec<Sums>, patch_size: usize) -> Sums {
 
     let mut sums = Sums::new(patch_size);
 
-    for coord in iproduct!(0..sums.width, 0..sums.width) {
-        sums[coord.0][coord.1] = large_sums[coord.0][coord.1]
-            + large_sums[coord.0 + small][coord.1 + small]
-            + small_sums[coord.0 + large][coord.1]
-            + small_sums[coord.0][coord.1 + large]
-            - all_sums[0][coord.0 + small][coord.1 + small];
+    for p in sums.sums.points() {
+        sums[p] = large_sums[p]
+            + large_sums[p + Vec2::new(small, small)]
+            + small_sums[p + Vec2::new(large, 0)]
+            + small_sums[p + Vec2::new(0, large)]
+            - all_sums[0][p + Vec2::new(small, small)];
     }
 
     sums
@@ -109,54 +125,87 @@ fn iter_sums_odd(all_sums: &Vec<Sums>, patch_size: usize) -> Sums {
 
 const GRID_SIZE: usize = 300;
 
-type Point = (usize, usize);
 type Identifier = (Point, i32);
-type Grid = [[i32; GRID_SIZE]; GRID_SIZE];
 
+// The total power of the square patch with its top left corner at each point.
 struct Sums {
-    sums: Vec<i32>,
+    sums: Grid<i32>,
     patch_size: usize,
-    width: usize,
 }
 
 impl Sums {
     fn new(patch_size: usize) -> Self {
         let width = GRID_SIZE - patch_size + 1;
         Sums {
-            sums: vec![0; width * width],
-            patch_size: patch_size,
-            width: width,
+            sums: Grid::new(width, width, 0),
+            patch_size,
         }
     }
 
+    fn width(&self) -> usize {
+        self.sums.width()
+    }
+
+    // Ties go to the last patch, by column then row.
     fn find_winner(&self) -> Identifier {
-        iproduct!(0..self.width, 0..self.width)
-            .map(|c| (c, self[c.0][c.1]))
+        iproduct!(0..self.width(), 0..self.width())
+            .map(Vec2::from)
+            .map(|c| (c, self[c]))
             .max_by(|a, b| a.1.cmp(&b.1))
             .unwrap()
     }
 
     fn _print(&self) {
         println!("Sums for Patch Size = {}:", self.patch_size);
-        for j in 0..self.width {
-            let line = (0..self.width)
-                .map(|i| format!("{:3} ", self[i][j]))
-                .collect::<String>();
+        for row in self.sums.rows() {
+            let line = row.iter().map(|s| format!("{:3} ", s)).collect::<String>();
             println!("{}", line);
         }
     }
 }
 
-impl std::ops::Index<usize> for Sums {
-    type Output = [i32];
+impl std::ops::Index<Point> for Sums {
+    type Output = i32;
+
+    fn index(&self, p: Point) -> &i32 {
+        &self.sums[p]
+    }
+}
 
-    fn index(&self, i: usize) -> &[i32] {
-        &self.sums[(i * self.width)..((i + 1) * self.width)]
+impl std::ops::IndexMut<Point> for Sums {
+    fn index_mut(&mut self, p: Point) -> &mut i32 {
+        &mut self.sums[p]
     }
 }
 
-impl std::ops::IndexMut<usize> for Sums {
-    fn index_mut(&mut self, i: usize) -> &mut [i32] {
-        &mut self.sums[(i * self.width)..((i + 1) * self.width)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    #[test]
+    fn power_level_examples() {
+        assert_eq!(calc_power(3, 5, 8), 4);
+        assert_eq!(calc_power(122, 79, 57), -5);
+        assert_eq!(calc_power(217, 196, 39), 0);
+        assert_eq!(calc_power(101, 153, 71), 4);
+    }
+
+    #[test]
+    fn part_a_examples() {
+        assert_eq!(
+            run_example::<Day11>(Part::A, &["18"]),
+            Answer::Coord(33, 45)
+        );
+        assert_eq!(
+            run_example::<Day11>(Part::A, &["42"]),
+            Answer::Coord(21, 61)
+        );
+    }
+
+    #[test]
+    fn part_b_examples() {
+        assert_eq!(run_example::<Day11>(Part::B, &["18"]), "90,269,16".into());
+        assert_eq!(run_example::<Day11>(Part::B, &["42"]), "232,251,12".into());
     }
 }
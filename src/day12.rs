@@ -1,62 +1,100 @@
-use crate::utils;
-use regex::Regex;
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+use crate::utils::{BitSet, Cycle, Input};
 use std::fmt;
 
-pub fn day12(lines: &mut Vec<String>) {
-    println!("Running Day 12 - a");
+#[derive(Default)]
+pub struct Day12 {
+    pots: Pots,
+    rules: Vec<Rule>,
+}
 
-    let initial_state = &lines[0];
+impl Solver for Day12 {
+    fn day(&self) -> u32 {
+        12
+    }
 
-    let initial_state = initial_state
-        .chars()
-        .skip(15)
-        .map(|c| c == '#')
-        .collect::<Vec<bool>>();
+    fn title(&self) -> &'static str {
+        "Subterranean Sustainability"
+    }
 
-    let mut pots = Pots::new(&initial_state);
-    // println!(" 0: {:?}", pots);
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        let (pots, rules) = parse_pots(input)?;
+        self.pots = pots;
+        self.rules = rules;
+        Ok(())
+    }
 
-    let rex = Regex::new(r"(.)(.)(.)(.)(.) => (.)").unwrap();
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        let mut pots = self.pots.clone();
+        // println!(" 0: {:?}", pots);
 
-    let mut rules = lines
-        .iter()
-        .skip(2)
-        .map(|l| rex.captures(l).unwrap())
-        .map(|c| Rule::new(&c))
-        .collect::<Vec<Rule>>();
-    rules.sort_by(|a, b| a.check.cmp(&b.check));
+        for _gen in 1..=20 {
+            pots = simulate_gen(&pots, &self.rules);
+            // println!("{:3}: {:?}", _gen, pots);
+            // println!("{},{}", _gen, pots.potted_sum());
+        }
 
-    for _gen in 1..=20 {
-        pots = simulate_gen(&mut pots, &rules);
-        // println!("{:3}: {:?}", _gen, pots);
-        // println!("{},{}", _gen, pots.potted_sum());
+        Ok(pots.potted_sum().into())
     }
 
-    println!("Potted Sum = {}", pots.potted_sum());
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(potted_sum_after(&self.pots, &self.rules, 50_000_000_000).into())
+    }
+}
 
-    println!("Running Day 12 - b");
+// Simulates until the plants settle into a repeating pattern, then skips ahead to the final generation.
+// The pattern can repeat further along the row, which moves every plant along by the same amount each lap.
+fn potted_sum_after(pots: &Pots, rules: &[Rule], generations: usize) -> isize {
+    let cycle = Cycle::find_by_key(
+        pots.clone(),
+        |pots| simulate_gen(pots, rules),
+        Pots::pattern,
+    );
+
+    let drift = cycle.repeat().potted_sum() - cycle.state_at(cycle.start()).potted_sum();
+    cycle.state_at(generations).potted_sum() + cycle.laps(generations) as isize * drift
+}
 
-    pots = Pots::new(&initial_state);
+fn parse_pots(input: &Input) -> Result<(Pots, Vec<Rule>)> {
+    let sections = input.sections();
+    let (state, rules) = match &sections[..] {
+        [state, rules] => (state, rules),
+        _ => return Err(Error::new("expected the initial state, then the rules")),
+    };
+
+    let initial_state = state
+        .single_line()?
+        .strip_prefix("initial state: ")
+        .ok_or_else(|| state.error_at(0, "expected the initial state"))?
+        .chars()
+        .map(|c| c == '#')
+        .collect::<Vec<bool>>();
 
-    let mut prev_sum: isize = 0;
-    let mut prev_diffs: [isize; 3] = [0, 1, 2];
-    let mut gen: isize = 0;
+    let mut rules = rules
+        .regex_rows(r"^([#.]{5}) => ([#.])$")?
+        .into_iter()
+        .map(|(check, result): (String, char)| Rule::new(&check, result))
+        .collect::<Vec<Rule>>();
 
-    // Find when the growth rate stabilizes for three generations
-    while prev_diffs[0] != prev_diffs[1] || prev_diffs[1] != prev_diffs[2] {
-        pots = simulate_gen(&mut pots, &rules);
-        let sum = pots.potted_sum();
-        prev_diffs[(gen % 3) as usize] = sum - prev_sum;
-        prev_sum = sum;
-        gen += 1;
+    // Patterns without a rule leave the pot empty.
+    for check in 0..32 {
+        if !rules.iter().any(|r| r.check == check) {
+            rules.push(Rule {
+                check,
+                result: false,
+            });
+        }
     }
+    rules.sort_by_key(|r| r.check);
+    rules.dedup_by_key(|r| r.check);
 
-    let final_sum: isize = prev_sum + (50_000_000_000 - gen) * prev_diffs[0];
-
-    println!("Potted Sum at Gen 50,000,000,000 = {}", final_sum);
+    Ok((Pots::new(&initial_state), rules))
 }
 
-fn simulate_gen(prev_pots: &mut Pots, rules: &Vec<Rule>) -> Pots {
+fn simulate_gen(prev_pots: &Pots, rules: &[Rule]) -> Pots {
+    let mut prev_pots = prev_pots.clone();
     prev_pots.try_grow();
     let mut pots = prev_pots.clone();
 
@@ -76,49 +114,51 @@ fn to_char(b: bool) -> char {
 }
 
 struct Rule {
-    check: u32,
+    check: usize,
     result: bool,
 }
 
 impl Rule {
-    fn new(c: &regex::Captures) -> Self {
-        let mut check: u32 = 0;
-        (0..5)
-            .filter(|i| c[i + 1] == *"#")
-            .for_each(|i| utils::set_bit(&mut check, i));
+    fn new(pattern: &str, result: char) -> Self {
+        // The leftmost pot is the lowest bit, the same as `Pots::get_pots`.
+        let check = pattern
+            .chars()
+            .rev()
+            .fold(0, |check, c| check << 1 | (c == '#') as usize);
         Rule {
-            check: check,
-            result: c[6] == *"#",
+            check,
+            result: result == '#',
         }
     }
 }
 
 impl std::fmt::Debug for Rule {
-    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        write!(
-            f,
-            "{}{}{}{}{} => {}",
-            to_char(utils::is_bit_set(&self.check, 0)),
-            to_char(utils::is_bit_set(&self.check, 1)),
-            to_char(utils::is_bit_set(&self.check, 2)),
-            to_char(utils::is_bit_set(&self.check, 3)),
-            to_char(utils::is_bit_set(&self.check, 4)),
-            to_char(self.result)
-        )
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pattern = (0..5)
+            .map(|i| to_char(self.check >> i & 1 != 0))
+            .collect::<String>();
+        write!(f, "{} => {}", pattern, to_char(self.result))
     }
 }
 
-#[derive(Clone)]
+// How many empty pots to add at a time when the plants spread past either end.
+const GROW: usize = 32;
+
+#[derive(Clone, Default)]
 struct Pots {
-    pots: Vec<u32>,
+    pots: BitSet,
+    // The bit that holds pot 0, leaving room for the pots left of it.
     offset: usize,
+    // The number of bits the pots span.
+    len: usize,
 }
 
 impl Pots {
-    fn new(state: &Vec<bool>) -> Self {
+    fn new(state: &[bool]) -> Self {
         let mut pots = Pots {
-            pots: vec![0; state.len() / 32 + 1],
+            pots: BitSet::with_capacity(state.len()),
             offset: 0,
+            len: (state.len() / GROW + 1) * GROW,
         };
 
         for i in state.iter().enumerate() {
@@ -129,81 +169,107 @@ impl Pots {
     }
 
     #[inline]
-    fn adapt_i(&self, i: isize) -> (usize, usize) {
-        let i = (i + self.offset as isize * 32) as usize;
-        (i % 32, i / 32)
-    }
-
-    fn get_pots(&self, i: isize) -> u32 {
-        let (mut i, mut idx) = self.adapt_i(i);
-
-        return match i {
-            0 | 1 | 30 | 31 => {
-                if i < 2 {
-                    idx -= 1;
-                    i += 32;
-                }
-
-                let mut pots: u64 = self.pots[idx + 1] as u64;
-                pots = pots << 32 | (self.pots[idx] as u64);
-                ((pots >> (i - 2)) & 0b11111) as u32
-            }
-            _ => (self.pots[idx] >> (i - 2)) & 0b11111,
-        };
+    fn adapt_i(&self, i: isize) -> usize {
+        (i + self.offset as isize) as usize
     }
 
-    fn get_pot(&self, i: isize) -> bool {
-        let (i, idx) = self.adapt_i(i);
-        utils::is_bit_set(&self.pots[idx], i)
+    // The five pots centred on `i`, with the leftmost as the lowest bit.
+    fn get_pots(&self, i: isize) -> u64 {
+        self.pots.window(self.adapt_i(i) - 2, 5)
     }
 
     fn set_pot(&mut self, i: isize, value: bool) {
-        let (i, idx) = self.adapt_i(i);
-        utils::set_bit_to(&mut self.pots[idx], i, value);
+        let i = self.adapt_i(i);
+        self.pots.set(i, value);
     }
 
+    // Keeps a few empty pots past the plants at both ends for the rules to look at.
     fn try_grow(&mut self) {
-        if (self.pots[0] & 0b1111) != 0 {
-            self.offset += 1;
-            self.pots.push(0);
-            let len = self.pots.len();
-            for i in 0..len - 1 {
-                self.pots[len - i - 1] = self.pots[len - i - 2];
-            }
-            self.pots[0] = 0;
+        if self.pots.window(0, 4) != 0 {
+            self.pots = &self.pots << GROW;
+            self.offset += GROW;
+            self.len += GROW;
         }
 
-        if (self.pots[self.pots.len() - 1] & 0xF0000000) != 0 {
-            self.pots.push(0);
+        if self.pots.window(self.len - 4, 4) != 0 {
+            self.len += GROW;
         }
     }
 
     fn get_pot_range(&self) -> (isize, isize) {
         (
-            self.offset as isize * -32 + 2,
-            (self.pots.len() as isize - self.offset as isize) * 32 - 2,
+            -(self.offset as isize) + 2,
+            (self.len - self.offset) as isize - 2,
         )
     }
 
+    // The plants, wherever they are in the row.
+    fn pattern(&self) -> BitSet {
+        let first = self.pots.iter().next().unwrap_or(0);
+        &self.pots >> first
+    }
+
     fn potted_sum(&self) -> isize {
-        let pot_range = self.get_pot_range();
-        (pot_range.0..pot_range.1)
-            .filter(|p| self.get_pot(*p))
+        self.pots
+            .iter()
+            .map(|bit| bit as isize - self.offset as isize)
             .sum()
     }
 }
 
 impl fmt::Debug for Pots {
-    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        write!(
-            f,
-            "{}",
-            self.pots
-                .iter()
-                .map(|p| (0..32)
-                    .map(|i| to_char(utils::is_bit_set(p, i)))
-                    .collect::<String>())
-                .collect::<String>()
-        )
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pots = (0..self.len)
+            .map(|bit| to_char(self.pots.contains(bit)))
+            .collect::<String>();
+        write!(f, "{}", pots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    const POTS: [&str; 16] = [
+        "initial state: #..#.#..##......###...###",
+        "",
+        "...## => #",
+        "..#.. => #",
+        ".#... => #",
+        ".#.#. => #",
+        ".#.## => #",
+        ".##.. => #",
+        ".#### => #",
+        "#.#.# => #",
+        "#.### => #",
+        "##.#. => #",
+        "##.## => #",
+        "###.. => #",
+        "###.# => #",
+        "####. => #",
+    ];
+
+    #[test]
+    fn part_a_example() {
+        assert_eq!(run_example::<Day12>(Part::A, &POTS), 325.into());
+    }
+
+    // There's no answer for part b of the example, so check that skipping ahead
+    // gets the same sum as simulating every generation.
+    #[test]
+    fn part_b_skips_ahead() {
+        let mut day = Day12::default();
+        day.parse(&Input::from(POTS.join("\n").as_str())).unwrap();
+
+        let mut pots = day.pots.clone();
+        for _ in 0..200 {
+            pots = simulate_gen(&pots, &day.rules);
+        }
+        assert_eq!(potted_sum_after(&day.pots, &day.rules, 20), 325);
+        assert_eq!(
+            potted_sum_after(&day.pots, &day.rules, 200),
+            pots.potted_sum()
+        );
     }
 }
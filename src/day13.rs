@@ -1,16 +1,81 @@
+use crate::answer::Answer;
+use crate::cancel;
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+use crate::utils::{Direction, Grid, Input, Point, Vec2};
 use std::cmp::Ordering;
 use std::collections::HashMap;
 
-pub fn day13(lines: &mut Vec<String>) {
-    println!("Running Day 13 - a");
+#[derive(Default)]
+pub struct Day13 {
+    map: Map,
+    carts: Vec<Cart>,
+}
+
+impl Solver for Day13 {
+    fn day(&self) -> u32 {
+        13
+    }
+
+    fn title(&self) -> &'static str {
+        "Mine Cart Madness"
+    }
+
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        let (map, carts) = parse_tracks(input.lines())?;
+        if carts.len() < 2 {
+            return Err(Error::new("there need to be at least two carts to crash"));
+        }
+        self.map = map;
+        self.carts = carts;
+        Ok(())
+    }
+
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        let map = &self.map;
+        let mut carts = self.carts.clone();
+
+        let mut crash = None;
+        while crash.is_none() {
+            cancel::check()?;
+            crash = cycle(map, &mut carts)?;
+        }
+
+        let crash = crash.unwrap();
+        Ok(Answer::Coord(crash.x as i64, crash.y as i64))
+    }
+
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        let map = &self.map;
+        let mut carts = self.carts.clone();
+
+        // _print_map(map, &carts);
+        while carts.iter().filter(|c| c.alive).count() > 1 {
+            cancel::check()?;
+            cycle(map, &mut carts)?;
+            // _print_map(map, &carts);
+        }
 
-    let mut map: Map = vec![vec![TrackType::None; 150]; 150];
+        let last = carts
+            .iter()
+            .find(|c| c.alive)
+            .ok_or_else(|| Error::new("every cart crashed, none are left"))?
+            .location;
+        Ok(Answer::Coord(last.x as i64, last.y as i64))
+    }
+}
+
+fn parse_tracks(lines: &[String]) -> Result<(Map, Vec<Cart>)> {
+    // Lines can stop short after the last track, so the map is as wide as the longest.
+    let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let mut map: Map = Grid::new(width, lines.len(), TrackType::None);
     let mut carts: Vec<Cart> = Vec::new();
 
     let mut cart_id = 0;
-    for (j, line) in lines.iter().enumerate() {
-        for (i, c) in line.chars().enumerate() {
-            map[i][j] = match c {
+    for (y, line) in lines.iter().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            let p = Vec2::new(x, y);
+            map[p] = match c {
                 '|' | '^' | 'v' => TrackType::Vertical,
                 '-' | '<' | '>' => TrackType::Horizontal,
                 '/' => TrackType::ForwardSlash,
@@ -19,86 +84,72 @@ pub fn day13(lines: &mut Vec<String>) {
                 _ => TrackType::None,
             };
 
-            match c {
-                '^' | 'v' | '<' | '>' => {
-                    carts.insert(
-                        cart_id,
-                        Cart::new(cart_id, (i, j), Direction::from_char(c).unwrap()),
-                    );
-                    cart_id += 1;
-                }
-                _ => {}
+            if let Some(direction) = Direction::from_arrow(c) {
+                carts.insert(cart_id, Cart::new(cart_id, p, direction));
+                cart_id += 1;
             }
         }
     }
 
-    // _print_map(&map, &carts);
-    while carts.iter().filter(|c| c.alive).count() > 1 {
-        cycle(&map, &mut carts);
-        // _print_map(&map, &carts);
-    }
-
-    println!("Running Day 13 - b");
-
-    println!(
-        "Last Cart Location = {:?}",
-        carts.iter().filter(|c| c.alive).next().unwrap().location
-    );
+    Ok((map, carts))
 }
 
-fn cycle(map: &Map, carts: &mut Vec<Cart>) {
+// Moves every living cart one step, in read order.
+// Returns the location of the first crash during this tick, if any.
+fn cycle(map: &Map, carts: &mut [Cart]) -> Result<Option<Point>> {
     let mut queue = carts.iter().filter(|c| c.alive).collect::<Vec<&Cart>>();
     queue.sort();
     let mut queue = queue.iter().map(|c| c.id).collect::<Vec<usize>>();
 
-    let all_alive = queue.len() == carts.len();
-
     let mut occupied = carts
         .iter()
         .filter(|c| c.alive)
         .map(|c| (c.location, c.id))
         .collect::<HashMap<Point, usize>>();
 
+    let mut crash = None;
+
     while let Some(cart_id) = queue.pop() {
         let cart = carts.get_mut(cart_id).unwrap();
-        occupied.remove(&cart.location);
-        cart.travel(&map);
+        if !cart.alive {
+            continue;
+        }
 
-        if occupied.contains_key(&cart.location) {
-            if all_alive {
-                println!("Crash at location {:?}", cart.location);
-            }
+        occupied.remove(&cart.location);
+        cart.travel(map)?;
 
-            let crashed_id = *occupied.get(&cart.location).unwrap();
+        if let Some(&crashed_id) = occupied.get(&cart.location) {
+            crash = crash.or(Some(cart.location));
+            occupied.remove(&cart.location);
             carts.get_mut(crashed_id).unwrap().alive = false;
             carts.get_mut(cart_id).unwrap().alive = false;
         } else {
             occupied.insert(cart.location, cart_id);
         }
     }
+
+    Ok(crash)
 }
 
-fn _print_map(map: &Map, carts: &Vec<Cart>) {
+fn _print_map(map: &Map, carts: &[Cart]) {
     let carts = carts
         .iter()
         .filter(|c| c.alive)
         .map(|c| (c.location, c))
         .collect::<HashMap<Point, &Cart>>();
 
-    for j in 0..150 {
-        for i in 0..150 {
-            if carts.contains_key(&(i, j)) {
-                print!("{}", carts[&(i, j)]._to_char());
-            } else {
-                print!("{}", map[i][j]._to_char());
-            }
+    for p in map.points() {
+        match carts.get(&p) {
+            Some(cart) => print!("{}", cart._to_char()),
+            None => print!("{}", map[p]._to_char()),
+        }
+        if p.x + 1 == map.width() {
+            println!();
         }
-        println!();
     }
 }
 
-type Point = (usize, usize);
-type Map = Vec<Vec<TrackType>>;
+type Map = Grid<TrackType>;
 
 #[derive(Eq, PartialEq, Clone)]
 struct Cart {
@@ -112,23 +163,27 @@ struct Cart {
 impl Cart {
     fn new(id: usize, location: Point, dir: Direction) -> Self {
         Cart {
-            id: id,
-            location: location,
+            id,
+            location,
             direction: dir,
             intersect: IntersectionBehavior::TurnLeft,
             alive: true,
         }
     }
 
-    fn travel(&mut self, map: &Map) {
-        match self.direction {
-            Direction::North => self.location.1 -= 1,
-            Direction::South => self.location.1 += 1,
-            Direction::East => self.location.0 += 1,
-            Direction::West => self.location.0 -= 1,
+    fn travel(&mut self, map: &Map) -> Result<()> {
+        let p = self.location;
+        let next = self.direction.checked_step(p);
+
+        let track = next.and_then(|p| map.get(p));
+        match track {
+            Some(TrackType::None) | None => {
+                return Err(Error::new(format!("a cart ran off the tracks at {}", p)))
+            }
+            Some(_) => self.location = next.unwrap(),
         }
 
-        match map[self.location.0][self.location.1] {
+        match track.unwrap() {
             TrackType::ForwardSlash => {
                 self.direction = match self.direction {
                     Direction::North | Direction::South => self.direction.turn_right(),
@@ -144,25 +199,25 @@ impl Cart {
             TrackType::Intersection => {
                 self.direction = match self.intersect {
                     IntersectionBehavior::TurnLeft => self.direction.turn_left(),
-                    IntersectionBehavior::GoStraight => self.direction.clone(),
+                    IntersectionBehavior::GoStraight => self.direction,
                     IntersectionBehavior::TurnRight => self.direction.turn_right(),
                 };
                 self.intersect = self.intersect.next();
             }
             _ => {}
         }
+
+        Ok(())
     }
 
     fn _to_char(&self) -> char {
-        self.direction._to_char()
+        self.direction.to_arrow()
     }
 }
 
 impl Ord for Cart {
     fn cmp(&self, other: &Cart) -> Ordering {
-        (other.location.1)
-            .cmp(&self.location.1)
-            .then(other.location.0.cmp(&self.location.0))
+        other.location.reading_cmp(&self.location)
     }
 }
 
@@ -172,55 +227,6 @@ impl PartialOrd for Cart {
     }
 }
 
-#[derive(Eq, PartialEq, Clone)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
-
-impl Direction {
-    fn _to_char(&self) -> char {
-        match self {
-            Direction::North => '^',
-            Direction::South => 'v',
-            Direction::East => '>',
-            Direction::West => '<',
-        }
-    }
-
-    fn from_char(c: char) -> Option<Self> {
-        match c {
-            '^' => Some(Direction::North),
-            'v' => Some(Direction::South),
-            '>' => Some(Direction::East),
-            '<' => Some(Direction::West),
-            _ => None,
-        }
-    }
-}
-
-impl Direction {
-    fn turn_left(&self) -> Self {
-        match self {
-            Direction::North => Direction::West,
-            Direction::South => Direction::East,
-            Direction::East => Direction::North,
-            Direction::West => Direction::South,
-        }
-    }
-
-    fn turn_right(&self) -> Self {
-        match self {
-            Direction::North => Direction::East,
-            Direction::South => Direction::West,
-            Direction::East => Direction::South,
-            Direction::West => Direction::North,
-        }
-    }
-}
-
 #[derive(Eq, PartialEq, Clone)]
 enum IntersectionBehavior {
     TurnLeft,
@@ -260,3 +266,30 @@ impl TrackType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    #[test]
+    fn part_a_example() {
+        let tracks = [
+            r"/->-\        ",
+            r"|   |  /----\",
+            r"| /-+--+-\  |",
+            r"| | |  | v  |",
+            r"\-+-/  \-+--/",
+            r"  \------/   ",
+        ];
+        assert_eq!(run_example::<Day13>(Part::A, &tracks), Answer::Coord(7, 3));
+    }
+
+    #[test]
+    fn part_b_example() {
+        let tracks = [
+            r"/>-<\  ", r"|   |  ", r"| /<+-\", r"| | | v", r"\>+</ |", r"  |   ^", r"  \<->/",
+        ];
+        assert_eq!(run_example::<Day13>(Part::B, &tracks), Answer::Coord(6, 4));
+    }
+}
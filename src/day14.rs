@@ -1,84 +1,117 @@
-use crate::utils;
-
-pub fn day14(lines: &mut Vec<String>) {
-    println!("Running Day 14 - a");
-
-    let target = utils::parse::<usize>(&lines[0]);
+use crate::answer::Answer;
+use crate::cancel;
+use crate::error::{Error, LineContext, Result};
+use crate::solver::Solver;
+use crate::utils::{self, Input};
+
+#[derive(Default)]
+pub struct Day14 {
+    target: usize,
+    // The target as a sequence of scores, keeping any leading zeros.
+    sequence: Vec<usize>,
+}
 
-    let mut recipes: Vec<usize> = vec![0; target + 11];
-    recipes[0] = 3;
-    recipes[1] = 7;
+impl Solver for Day14 {
+    fn day(&self) -> u32 {
+        14
+    }
 
-    let mut count: usize = 2;
-    let mut elves: (usize, usize) = (0, 1);
+    fn title(&self) -> &'static str {
+        "Chocolate Charts"
+    }
 
-    while count < target + 10 {
-        let sum = recipes[elves.0] + recipes[elves.1];
-        let digits = split_digits(sum);
-        for i in 0..digits.len() {
-            recipes[count + i] = digits[i];
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        let line = input.single_line()?;
+        // Each digit is a score, so nothing else can be allowed through, not even a sign.
+        if line.is_empty() || !line.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::at_line(
+                0,
+                format!("expected a number of recipes, got `{}`", line),
+            ));
         }
-        count += digits.len();
-
-        elves.0 = (elves.0 + recipes[elves.0] + 1) % count;
-        elves.1 = (elves.1 + recipes[elves.1] + 1) % count;
-        // _print_recipes(&recipes, count, elves);
+        self.target = utils::parse(line).line(0)?;
+        self.sequence = line.bytes().map(|b| (b - b'0') as usize).collect();
+        Ok(())
     }
 
-    let scores = (target..target + 10)
-        .map(|i| recipes[i])
-        .map(|r| ((r + 48) as u8) as char)
-        .collect::<String>();
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        let target = self.target;
 
-    println!("Scores = {}", scores);
+        let mut recipes: Vec<usize> = vec![0; target + 11];
+        recipes[0] = 3;
+        recipes[1] = 7;
 
-    println!("Running Day 14 - b");
+        let mut count: usize = 2;
+        let mut elves: (usize, usize) = (0, 1);
 
-    let target = split_digits(target);
-    let mut recipes: Vec<usize> = vec![0; 1000000];
-    recipes[0] = 3;
-    recipes[1] = 7;
+        while count < target + 10 {
+            let sum = recipes[elves.0] + recipes[elves.1];
+            let digits = split_digits(sum);
+            recipes[count..count + digits.len()].copy_from_slice(&digits);
+            count += digits.len();
 
-    count = 2;
-    elves = (0, 1);
-    let mut index = None;
-    let mut digits = (None, None);
-
-    while index.is_none() {
-        if recipes.len() < count + 2 {
-            recipes.extend_from_slice(&vec![0; 1000000]);
+            elves.0 = (elves.0 + recipes[elves.0] + 1) % count;
+            elves.1 = (elves.1 + recipes[elves.1] + 1) % count;
+            // _print_recipes(&recipes, count, elves);
         }
 
-        let sum = recipes[elves.0] + recipes[elves.1];
-        split_digits_b(sum, &mut digits);
-        let mut digit_count = 1;
-        if digits.0.is_some() {
-            recipes[count] = digits.0.unwrap();
-            count += 1;
-            digit_count += 1;
-        }
-        recipes[count] = digits.1.unwrap();
-        count += 1;
+        let scores = (target..target + 10)
+            .map(|i| recipes[i])
+            .map(|r| ((r + 48) as u8) as char)
+            .collect::<String>();
 
-        elves.0 = (elves.0 + recipes[elves.0] + 1) % count;
-        elves.1 = (elves.1 + recipes[elves.1] + 1) % count;
-        // _print_recipes(&recipes, count, elves);
+        Ok(scores.into())
+    }
 
-        for i in 0..digit_count {
-            let end = count - i;
-            if end < target.len() {
-                break;
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        let target = &self.sequence;
+        let mut recipes: Vec<usize> = vec![0; 1000000];
+        recipes[0] = 3;
+        recipes[1] = 7;
+
+        let mut count: usize = 2;
+        let mut elves: (usize, usize) = (0, 1);
+        let mut index = None;
+        let mut digits = (None, None);
+
+        while index.is_none() {
+            if recipes.len() < count + 2 {
+                // The sequence might never turn up.
+                cancel::check()?;
+                recipes.resize(recipes.len() + 1000000, 0);
             }
-            let start = count - target.len() - i;
 
-            if &recipes[start..end] == &target[..] {
-                index = Some(start);
-                break;
+            let sum = recipes[elves.0] + recipes[elves.1];
+            split_digits_b(sum, &mut digits);
+            let mut digit_count = 1;
+            if let Some(digit) = digits.0 {
+                recipes[count] = digit;
+                count += 1;
+                digit_count += 1;
+            }
+            recipes[count] = digits.1.unwrap();
+            count += 1;
+
+            elves.0 = (elves.0 + recipes[elves.0] + 1) % count;
+            elves.1 = (elves.1 + recipes[elves.1] + 1) % count;
+            // _print_recipes(&recipes, count, elves);
+
+            for i in 0..digit_count {
+                let end = count - i;
+                if end < target.len() {
+                    break;
+                }
+                let start = count - target.len() - i;
+
+                if recipes[start..end] == target[..] {
+                    index = Some(start);
+                    break;
+                }
             }
         }
-    }
 
-    println!("Recipes Left of Input = {}", index.unwrap());
+        Ok(index.unwrap().into())
+    }
 }
 
 fn split_digits(mut num: usize) -> Vec<usize> {
@@ -104,15 +137,51 @@ fn split_digits_b(num: usize, digits: &mut (Option<usize>, Option<usize>)) {
     }
 }
 
-fn _print_recipes(recipes: &Vec<usize>, count: usize, elves: (usize, usize)) {
-    for i in 0..count {
+fn _print_recipes(recipes: &[usize], count: usize, elves: (usize, usize)) {
+    for (i, recipe) in recipes.iter().enumerate().take(count) {
         if i == elves.0 {
-            print!("({})", recipes[i]);
+            print!("({})", recipe);
         } else if i == elves.1 {
-            print!("[{}]", recipes[i]);
+            print!("[{}]", recipe);
         } else {
-            print!(" {} ", recipes[i]);
+            print!(" {} ", recipe);
         }
     }
     println!();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    #[test]
+    fn part_a_examples() {
+        assert_eq!(run_example::<Day14>(Part::A, &["9"]), "5158916779".into());
+        assert_eq!(run_example::<Day14>(Part::A, &["5"]), "0124515891".into());
+        assert_eq!(run_example::<Day14>(Part::A, &["18"]), "9251071085".into());
+        assert_eq!(
+            run_example::<Day14>(Part::A, &["2018"]),
+            "5941429882".into()
+        );
+    }
+
+    #[test]
+    fn part_b_examples() {
+        assert_eq!(run_example::<Day14>(Part::B, &["51589"]), 9.into());
+        assert_eq!(run_example::<Day14>(Part::B, &["01245"]), 5.into());
+        assert_eq!(run_example::<Day14>(Part::B, &["92510"]), 18.into());
+        assert_eq!(run_example::<Day14>(Part::B, &["59414"]), 2018.into());
+    }
+
+    #[test]
+    fn parse_only_accepts_digits() {
+        for line in ["+5", "-5", "5a"] {
+            let e = Day14::default().parse(&Input::from(line)).unwrap_err();
+            assert_eq!(
+                e.to_string(),
+                format!("line 1: expected a number of recipes, got `{}`", line)
+            );
+        }
+    }
+}
@@ -1,39 +1,55 @@
+use crate::answer::Answer;
+use crate::cancel;
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+use crate::utils::{search, Grid, Input, Order, Point};
 use itertools::Itertools;
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
-use std::collections::VecDeque;
 
-pub fn day15(lines: &mut Vec<String>) {
-    println!("Running Day 15 - a");
-
-    let orig_map = Map::new(&lines);
+#[derive(Default)]
+pub struct Day15 {
+    map: Map,
+}
 
-    let mut map = orig_map.clone();
-    let rounds = simulate_combat(&mut map);
+impl Solver for Day15 {
+    fn day(&self) -> u32 {
+        15
+    }
 
-    println!(
-        "Combat finished after {} rounds, outcome = {}",
-        rounds,
-        calc_outcome(&map, rounds)
-    );
+    fn title(&self) -> &'static str {
+        "Beverage Bandits"
+    }
 
-    println!("Running Day 15 - b");
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        self.map = Map::new(input)?;
+        Ok(())
+    }
 
-    let mut result = (false, 0);
-    let mut strength = 2;
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        let mut map = self.map.clone();
+        let rounds = simulate_combat(&mut map)?;
 
-    while !result.0 {
-        strength += 1;
-        map = orig_map.clone();
-        result = simulate_elf_victory(strength, &mut map);
+        Ok(calc_outcome(&map, rounds).into())
     }
 
-    println!(
-        "Combat finished with strength {} after {} rounds, outcome = {}",
-        strength,
-        result.1,
-        calc_outcome(&map, result.1)
-    );
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        let orig_map = &self.map;
+
+        let mut map = orig_map.clone();
+        let mut result = (false, 0);
+        let mut strength = 2;
+
+        while !result.0 {
+            // Past full health every hit is a kill, so more strength can't help.
+            if strength >= 200 {
+                return Err(Error::new("the elves can't win without losses"));
+            }
+            strength += 1;
+            map = orig_map.clone();
+            result = simulate_elf_victory(strength, &mut map)?;
+        }
+
+        Ok(calc_outcome(&map, result.1).into())
+    }
 }
 
 fn calc_outcome(map: &Map, rounds: usize) -> usize {
@@ -47,18 +63,19 @@ fn calc_outcome(map: &Map, rounds: usize) -> usize {
 // Simulates a full combat.
 // Modifies map in place.
 // Returns the number of completed rounds.
-fn simulate_combat(map: &mut Map) -> usize {
+fn simulate_combat(map: &mut Map) -> Result<usize> {
     let mut round = 0;
     while !simulate_round(map) {
+        cancel::check()?;
         round += 1;
     }
-    round
+    Ok(round)
 }
 
 // Simulates combat until a single elf dies, or the elves win.
 // Modifies map in place.
 // Returns (were elves victorious, the number of completed rounds).
-fn simulate_elf_victory(strength: usize, map: &mut Map) -> (bool, usize) {
+fn simulate_elf_victory(strength: usize, map: &mut Map) -> Result<(bool, usize)> {
     map.elves.iter_mut().for_each(|u| u.strength = strength);
 
     let mut victory = true;
@@ -66,46 +83,52 @@ fn simulate_elf_victory(strength: usize, map: &mut Map) -> (bool, usize) {
     let mut round = 0;
 
     while victory && !ended {
+        cancel::check()?;
         ended = simulate_round(map);
         victory = map.elves.iter().filter(|u| !u.alive).count() == 0;
         round += 1;
     }
 
-    (victory, round - 1)
+    Ok((victory, round - 1))
 }
 
 // Simulates a round of combat, ending early when no enemies are found for a unit.
 // Returns whether combat has ended entirely.
 fn simulate_round(map: &mut Map) -> bool {
-    let mut queue = build_queue(&map);
+    // Without both sides there's nobody to find enemies, and so nothing to end combat.
+    if !map.elves.iter().any(|u| u.alive) || !map.goblins.iter().any(|u| u.alive) {
+        return true;
+    }
+
+    let mut queue = build_queue(map);
 
     while let Some(mut entry) = queue.pop() {
         if map[entry].is_open() {
             continue;
         }
 
-        let targets = find_targets(entry, &map);
+        let targets = find_targets(entry, map);
 
         if targets.is_empty() {
             return true;
         }
 
-        if !target_adjacent(entry, &map, &targets) {
-            let target_points = find_target_points(targets, &map);
+        if !target_adjacent(entry, map, &targets) {
+            let target_points = find_target_points(targets, map);
             if target_points.is_empty() {
                 continue;
             }
 
-            let dest = find_destination(entry, &target_points, &map);
-            if dest.is_some() {
-                map.swap(entry, dest.unwrap());
-                entry = dest.unwrap();
+            let dest = find_destination(entry, &target_points, map);
+            if let Some(dest) = dest {
+                map.swap(entry, dest);
+                entry = dest;
             }
         }
 
-        let mut enemies = get_enemy_neighbors(entry, &map);
+        let mut enemies = get_enemy_neighbors(entry, map);
         if !enemies.is_empty() {
-            enemies.sort_by(|a, b| a.hp.cmp(&b.hp).then(b.location.cmp(&a.location)));
+            enemies.sort_by(|a, b| a.hp.cmp(&b.hp).then(a.location.reading_cmp(&b.location)));
             let target = enemies.first().unwrap();
             map.attack(entry, target.location);
         }
@@ -114,15 +137,16 @@ fn simulate_round(map: &mut Map) -> bool {
     false
 }
 
-// Build a queue of points relating to all remaining units, in read order.
-fn build_queue(map: &Map) -> BinaryHeap<Point> {
-    let mut queue = BinaryHeap::new();
-    for p in iproduct!(0..map.width(), 0..map.height()) {
+// Build a queue of points relating to all remaining units, popping in read order.
+fn build_queue(map: &Map) -> Vec<Point> {
+    let mut queue = Vec::new();
+    for p in map.tiles.points() {
         match map[p] {
-            Tile::Goblin(_) | Tile::Elf(_) => queue.push(Point(p.0, p.1)),
+            Tile::Goblin(_) | Tile::Elf(_) => queue.push(p),
             _ => (),
         }
     }
+    queue.reverse();
     queue
 }
 
@@ -141,9 +165,9 @@ fn find_targets(p: Point, map: &Map) -> Vec<&Unit> {
 // Determine if a target unit is adjacent to Point p.
 fn target_adjacent(p: Point, map: &Map, targets: &Vec<&Unit>) -> bool {
     let target_locations: Vec<Point> = targets.iter().map(|t| t.location).collect();
-    get_neighbors(p, &map)
+    get_neighbors(p, map)
         .iter()
-        .find(|p| target_locations.contains(&p))
+        .find(|p| target_locations.contains(p))
         .is_some()
 }
 
@@ -152,167 +176,52 @@ fn target_adjacent(p: Point, map: &Map, targets: &Vec<&Unit>) -> bool {
 fn find_target_points(targets: Vec<&Unit>, map: &Map) -> Vec<Point> {
     targets
         .iter()
-        .flat_map(|t| get_open_neighbors(t.location, &map))
+        .flat_map(|t| get_open_neighbors(t.location, map))
         .unique()
-        .sorted_by(|a, b| b.cmp(&a))
+        .sorted_by(|a, b| a.reading_cmp(b))
         .collect()
 }
 
 // Find a adjacent destination Point to move a unit from start to that is one step closer,
 // in read order, to the closest reachable target, in read order.
-fn _find_destination_astar(
-    start: Point,
-    target_points: &BinaryHeap<Point>,
-    map: &Map,
-) -> Option<Point> {
-    use pathfinding::directed::astar;
-
-    let choices: Vec<(usize, Point)> = target_points
-        .iter()
-        .filter_map(|tp| {
-            let a_stars = astar::astar_bag_collect(
-                &start,
-                |&p| get_open_neighbors(p, &map).into_iter().map(|p| (p, 1)),
-                |p| _calc_manhattan(p, &tp),
-                |p| *p == *tp,
-            );
-
-            match a_stars {
-                Some(a_star_info) => {
-                    let mut paths = a_star_info.0;
-                    paths.sort_by(|a, b| b[1].cmp(&a[1]));
-                    Some((a_star_info.1, paths[0][1]))
-                }
-                None => None,
-            }
-        })
-        .sorted_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)))
-        .collect();
-
-    match choices.first() {
-        Some(c) => Some(c.1),
-        None => None,
-    }
-}
-
-fn _find_destination_bfs(
-    start: Point,
-    target_points: &BinaryHeap<Point>,
-    map: &Map,
-) -> Option<Point> {
-    use pathfinding::directed::bfs;
-
-    let bfs_results = target_points
-        .iter()
-        .map(|&tp| {
-            (
-                tp,
-                bfs::bfs(&start, |&p| get_open_neighbors(p, &map), |p| *p == tp),
-            )
-        })
-        .filter(|(_, bfs)| bfs.is_some())
-        .map(|(tp, bfs)| (tp, bfs.unwrap().len()))
-        .sorted_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
-        .collect::<Vec<(Point, usize)>>();
-
-    if bfs_results.first().is_none() {
-        return None;
-    }
-
-    let (tp, distance) = bfs_results.first().unwrap();
-
-    Some(
-        *get_open_neighbors(start, &map)
-            .iter()
-            .map(|d| {
-                (
-                    d,
-                    bfs::bfs(d, |&p| get_open_neighbors(p, &map), |p| *p == *tp),
-                )
-            })
-            .filter(|(_, bfs)| bfs.is_some())
-            .map(|(d, bfs)| (d, bfs.unwrap().len()))
-            .filter(|(_, bfs)| *bfs == distance - 1)
-            .next()
-            .unwrap()
-            .0,
-    )
-}
-
-fn find_destination(start: Point, target_points: &Vec<Point>, map: &Map) -> Option<Point> {
-    // Queue stores (node, bfs) tuples.
-    let mut queue: VecDeque<(Point, usize)> = VecDeque::new();
-
-    // Each entry in BFS_Map contains (prev, bfs) tuple.
-    let mut bfs_map: Vec<Vec<Option<(Point, usize)>>> = vec![vec![None; map.height()]; map.width()];
-
-    // Prime the pump
-    queue.push_back((start, 0));
-    bfs_map[start.0][start.1] = Some((start, 0));
-    let mut target_bfs = None;
-
-    while let Some((node, bfs)) = queue.pop_front() {
-        // Check if we're now processing a closest target point
-        // (meaning all closest target points have already been visited).
-        if target_points.contains(&node) {
-            target_bfs = Some(bfs);
-            break;
-        }
-
-        // Visit all neighbors to node, and queue them (in read order).
-        let neighbors = get_open_neighbors(node, &map);
-        for neighbor in neighbors {
-            if bfs_map[neighbor.0][neighbor.1].is_none() {
-                bfs_map[neighbor.0][neighbor.1] = Some((node, bfs + 1));
-                queue.push_back((neighbor, bfs + 1));
-            }
-        }
-    }
-    if target_bfs.is_none() {
-        return None;
-    }
-    let target_bfs = target_bfs.unwrap();
+fn find_destination(start: Point, target_points: &[Point], map: &Map) -> Option<Point> {
+    let open_neighbors = |p| {
+        map.tiles
+            .neighbors4(p, Order::Reading)
+            .filter(move |&n| map[n].is_open())
+    };
 
-    // Find the first target we reached in target_bfs, in reading order.
-    let tp = *target_points
+    // Stop at the closest target points.
+    // They're in read order, so the first of them wins.
+    let from_start = search::bfs_until(
+        map.width(),
+        map.height(),
+        vec![start],
+        open_neighbors,
+        |p| target_points.contains(&p),
+    );
+    let (bfs, tp) = target_points
         .iter()
-        .filter(|p| bfs_map[p.0][p.1].is_some())
-        .filter(|p| bfs_map[p.0][p.1].unwrap().1 == target_bfs)
-        .next()
-        .unwrap();
-
-    let mut node = tp;
-    let mut prev = bfs_map[tp.0][tp.1].unwrap().0;
-
-    // Walk the same path we took to get there, backward.
-    while prev != start {
-        node = prev;
-        prev = bfs_map[node.0][node.1].unwrap().0;
-    }
-    Some(node)
+        .filter_map(|&p| from_start.distance(p).map(|bfs| (bfs, p)))
+        .min_by_key(|&(bfs, _)| bfs)?;
+
+    // Searching back from the target finds every first step on a shortest path.
+    let from_target = search::bfs_until(map.width(), map.height(), vec![tp], open_neighbors, |p| {
+        p.manhattan(start) == 1
+    });
+    get_open_neighbors(start, map)
+        .into_iter()
+        .find(|&p| from_target.distance(p) == Some(bfs - 1))
 }
 
 // Get neighboring points to p, within map's bounds (in read order).
 fn get_neighbors(p: Point, map: &Map) -> Vec<Point> {
-    let mut points = Vec::new();
-    if p.1 > 0 {
-        points.push(Point(p.0, p.1 - 1));
-    }
-    if p.0 > 0 {
-        points.push(Point(p.0 - 1, p.1));
-    }
-    if p.0 + 1 < map.width() {
-        points.push(Point(p.0 + 1, p.1));
-    }
-    if p.1 + 1 < map.height() {
-        points.push(Point(p.0, p.1 + 1));
-    }
-    points
+    map.tiles.neighbors4(p, Order::Reading).collect()
 }
 
 // Gets open neighboring points to p, within map's bounds (in read order).
 fn get_open_neighbors(p: Point, map: &Map) -> Vec<Point> {
-    get_neighbors(p, &map)
+    get_neighbors(p, map)
         .into_iter()
         .filter(|i| map[*i].is_open())
         .collect()
@@ -320,7 +229,7 @@ fn get_open_neighbors(p: Point, map: &Map) -> Vec<Point> {
 
 // Gets enemy neighbors to p (in read order).
 fn get_enemy_neighbors(p: Point, map: &Map) -> Vec<&Unit> {
-    let neighbors = get_neighbors(p, &map);
+    let neighbors = get_neighbors(p, map);
 
     let enemies = match map[p] {
         Tile::Goblin(_) => &map.elves,
@@ -334,11 +243,6 @@ fn get_enemy_neighbors(p: Point, map: &Map) -> Vec<&Unit> {
         .collect()
 }
 
-fn _calc_manhattan(a: &Point, b: &Point) -> usize {
-    use pathfinding::utils::absdiff;
-    absdiff(a.0, b.0) + absdiff(a.1, b.1)
-}
-
 #[derive(Clone)]
 enum Tile {
     Wall,
@@ -366,95 +270,71 @@ impl Tile {
         }
     }
 
-    fn _to_char(&self) -> char {
-        match self {
-            Tile::Wall => '#',
-            Tile::Open => '.',
-            Tile::Goblin(_) => 'G',
-            Tile::Elf(_) => 'E',
-        }
-    }
-
     fn is_open(&self) -> bool {
-        match self {
-            Tile::Open => true,
-            _ => false,
-        }
-    }
-}
-
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-struct Point(usize, usize);
-
-impl Ord for Point {
-    fn cmp(&self, other: &Point) -> Ordering {
-        other.1.cmp(&self.1).then(other.0.cmp(&self.0))
+        matches!(self, Tile::Open)
     }
 }
 
-impl PartialOrd for Point {
-    fn partial_cmp(&self, other: &Point) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl std::fmt::Display for Tile {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let c = match self {
+            Tile::Wall => '#',
+            Tile::Open => '.',
+            Tile::Goblin(_) => 'G',
+            Tile::Elf(_) => 'E',
+        };
+        write!(f, "{}", c)
     }
 }
 
-type Tiles = Vec<Vec<Tile>>;
-
-#[derive(Clone)]
+#[derive(Clone, Default)]
 struct Map {
-    tiles: Tiles,
+    tiles: Grid<Tile>,
     goblins: Vec<Unit>,
     elves: Vec<Unit>,
 }
 
 impl Map {
-    fn new(lines: &Vec<String>) -> Self {
+    fn new(input: &Input) -> Result<Self> {
         let mut goblins = 0;
         let mut elves = 0;
 
-        let tiles = lines
-            .iter()
-            .map(|l| {
-                l.chars()
-                    .map(|c| Tile::from_char(c, &mut goblins, &mut elves).unwrap())
-                    .collect()
-            })
-            .collect();
+        let tiles = Grid::parse(input, |c| Tile::from_char(c, &mut goblins, &mut elves))?;
 
         let mut map = Map {
-            tiles: tiles,
+            tiles,
             goblins: Vec::new(),
             elves: Vec::new(),
         };
 
-        for p in iproduct!(0..map.height(), 0..map.width()) {
-            match map.tiles[p.0][p.1] {
+        for p in map.tiles.points() {
+            match map.tiles[p] {
                 Tile::Goblin(_) => {
-                    map.goblins.push(Unit::new(Point(p.1, p.0)));
+                    map.goblins.push(Unit::new(p));
                 }
                 Tile::Elf(_) => {
-                    map.elves.push(Unit::new(Point(p.1, p.0)));
+                    map.elves.push(Unit::new(p));
                 }
                 _ => (),
             }
         }
 
-        map
+        Ok(map)
     }
 
     fn width(&self) -> usize {
-        self.tiles[0].len()
+        self.tiles.width()
     }
 
     fn height(&self) -> usize {
-        self.tiles.len()
+        self.tiles.height()
     }
 
     fn swap(&mut self, a: Point, b: Point) {
         let tile_a = self[a].clone();
         let tile_b = self[b].clone();
-        self.tiles[a.1][a.0] = tile_b;
-        self.tiles[b.1][b.0] = tile_a;
+        self.tiles[a] = tile_b;
+        self.tiles[b] = tile_a;
 
         match self[a] {
             Tile::Goblin(id) => self.goblins[id].location = a,
@@ -487,15 +367,12 @@ impl Map {
         } else {
             unit_b.hp = 0;
             unit_b.alive = false;
-            self.tiles[b.1][b.0] = Tile::Open;
+            self.tiles[b] = Tile::Open;
         }
     }
 
     fn _print(&self) {
-        for line in self.tiles.iter() {
-            println!("{}", line.iter().map(|t| t._to_char()).collect::<String>());
-        }
-        println!();
+        println!("{}", self.tiles);
     }
 }
 
@@ -503,15 +380,7 @@ impl std::ops::Index<Point> for Map {
     type Output = Tile;
 
     fn index(&self, i: Point) -> &Tile {
-        &self.tiles[i.1][i.0]
-    }
-}
-
-impl std::ops::Index<(usize, usize)> for Map {
-    type Output = Tile;
-
-    fn index(&self, i: (usize, usize)) -> &Tile {
-        &self.tiles[i.1][i.0]
+        &self.tiles[i]
     }
 }
 
@@ -526,10 +395,126 @@ struct Unit {
 impl Unit {
     fn new(location: Point) -> Self {
         Unit {
-            location: location,
+            location,
             strength: 3,
             hp: 200,
             alive: true,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    // Each combat from the puzzle, with the outcome of both parts.
+    // The second combat has no outcome for part b.
+    #[rustfmt::skip]
+    const COMBATS: [(&[&str], usize, Option<usize>); 6] = [
+        (
+            &[
+                "#######",
+                "#.G...#",
+                "#...EG#",
+                "#.#.#G#",
+                "#..G#E#",
+                "#.....#",
+                "#######",
+            ],
+            27730,
+            Some(4988),
+        ),
+        (
+            &[
+                "#######",
+                "#G..#E#",
+                "#E#E.E#",
+                "#G.##.#",
+                "#...#E#",
+                "#...E.#",
+                "#######",
+            ],
+            36334,
+            None,
+        ),
+        (
+            &[
+                "#######",
+                "#E..EG#",
+                "#.#G.E#",
+                "#E.##E#",
+                "#G..#.#",
+                "#..E#.#",
+                "#######",
+            ],
+            39514,
+            Some(31284),
+        ),
+        (
+            &[
+                "#######",
+                "#E.G#.#",
+                "#.#G..#",
+                "#G.#.G#",
+                "#G..#.#",
+                "#...E.#",
+                "#######",
+            ],
+            27755,
+            Some(3478),
+        ),
+        (
+            &[
+                "#######",
+                "#.E...#",
+                "#.#..G#",
+                "#.###.#",
+                "#E#G#G#",
+                "#...#G#",
+                "#######",
+            ],
+            28944,
+            Some(6474),
+        ),
+        (
+            &[
+                "#########",
+                "#G......#",
+                "#.E.#...#",
+                "#..##..G#",
+                "#...##..#",
+                "#...#...#",
+                "#.G...G.#",
+                "#.....G.#",
+                "#########",
+            ],
+            18740,
+            Some(1140),
+        ),
+    ];
+
+    #[test]
+    fn part_a_examples() {
+        for &(map, outcome, _) in COMBATS.iter() {
+            assert_eq!(run_example::<Day15>(Part::A, map), outcome.into());
+        }
+    }
+
+    #[test]
+    fn part_b_examples() {
+        for &(map, _, outcome) in COMBATS.iter() {
+            if let Some(outcome) = outcome {
+                assert_eq!(run_example::<Day15>(Part::B, map), outcome.into());
+            }
+        }
+    }
+
+    #[test]
+    fn combat_without_both_sides_is_over_at_once() {
+        for map in [&["###", "#.#", "###"][..], &["####", "#EE#", "####"]] {
+            assert_eq!(run_example::<Day15>(Part::A, map), 0.into());
+            assert_eq!(run_example::<Day15>(Part::B, map), 0.into());
+        }
+    }
+}
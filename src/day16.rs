@@ -1,100 +1,126 @@
-use crate::emulator::{Emulator, Instruction, InstructionData, Op, Program, Registers};
-use crate::utils;
+use crate::answer::Answer;
+use crate::emulator::{
+    Emulator, Instruction, InstructionData, Op, Program, Registers, REGISTER_COUNT,
+};
+use crate::error::{Error, LineContext, Result};
+use crate::solver::Solver;
+use crate::utils::{self, Input};
 use itertools::Itertools;
-use regex::Regex;
 use std::collections::HashMap;
 
-pub fn day16(lines: &mut Vec<String>) {
-    println!("Running Day 16 - a");
+// An op code, with the ops its sample behaves like.
+type Sample = (usize, Vec<Op>);
 
-    let mut split = 0;
-    for i in 0..lines.len() {
-        if lines[i] == "" && lines[i] == lines[i + 1] && lines[i + 1] == lines[i + 2] {
-            split = i;
-            break;
-        }
-    }
-
-    let input1 = &lines[..split + 1];
-    let input2 = &lines[split + 3..];
+#[derive(Default)]
+pub struct Day16 {
+    samples: Vec<Sample>,
+    program: Input,
+}
 
-    let rex = Regex::new(r"....... \[(\d+), (\d+), (\d+), (\d+)\]").unwrap();
+impl Solver for Day16 {
+    fn day(&self) -> u32 {
+        16
+    }
 
-    let mut result = 0;
-    let mut samples: Vec<(usize, Vec<Op>)> = Vec::new();
+    fn title(&self) -> &'static str {
+        "Chronal Classification"
+    }
 
-    let mut iter = input1.iter().take(split + 1);
-    while let Some((before, instruction, after, _)) = iter.next_tuple::<(_, _, _, _)>() {
-        let before = parse_registers(before, &rex);
-        let instruction = parse_raw_instruction(&instruction);
-        let after = parse_registers(after, &rex);
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        let sections = input.sections();
+        let (program, samples) = sections
+            .split_last()
+            .ok_or_else(|| Error::new("expected samples, then the test program"))?;
+        self.samples = parse_samples(samples)?;
+        self.program = program.clone();
+        Ok(())
+    }
 
-        let behaving_ops = test_ops(&before, &instruction, &after);
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        let result = self
+            .samples
+            .iter()
+            .filter(|(_, ops)| ops.len() >= 3)
+            .count();
 
-        if behaving_ops.len() >= 3 {
-            result += 1;
-        }
+        Ok(result.into())
+    }
 
-        samples.push((instruction[0], behaving_ops));
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        let mut samples = self.samples.clone();
+
+        let op_codes = deduce_op_codes(&mut samples);
+
+        let mut emulator = Emulator::new();
+        let program = Program {
+            ip_register: 4,
+            instructions: self
+                .program
+                .lines()
+                .iter()
+                .enumerate()
+                .map(|(idx, i)| parse_instruction(i, &op_codes).line(self.program.start() + idx))
+                .collect::<Result<_>>()?,
+        };
+        emulator.run_program(&program)?;
+
+        Ok(emulator.registers[0].into())
     }
+}
 
-    println!("Samples behaving as 3+ opcodes = {}", result);
+// Pairs each sample with the ops it behaves like.
+fn parse_samples(sections: &[Input]) -> Result<Vec<Sample>> {
+    let mut samples: Vec<Sample> = Vec::new();
 
-    println!("Running Day 16 - b");
+    for sample in sections {
+        let idx = sample.start();
+        let (before, instruction, after) = match sample.lines() {
+            [before, instruction, after] => (before, instruction, after),
+            _ => return Err(sample.error_at(0, "expected a sample of three lines")),
+        };
+        let before = parse_registers(before).line(idx)?;
+        let instruction = parse_raw_instruction(instruction).line(idx + 1)?;
+        let after = parse_registers(after).line(idx + 2)?;
 
-    let op_codes = deduce_op_codes(&mut samples);
+        let behaving_ops = test_ops(&before, &instruction, &after);
 
-    let mut emulator = Emulator::new();
-    let program = Program {
-        ip_register: 4,
-        instructions: input2
-            .iter()
-            .map(|i| parse_instruction(i, &op_codes))
-            .collect(),
-    };
-    emulator.run_program(&program);
+        samples.push((instruction[0], behaving_ops));
+    }
 
-    println!("Program Results = {:?}", emulator.registers);
+    Ok(samples)
 }
 
-fn parse_registers(registers: &String, rex: &Regex) -> Registers {
-    let c = rex.captures(registers).unwrap();
-    [
-        utils::parse::<usize>(&c[1]),
-        utils::parse::<usize>(&c[2]),
-        utils::parse::<usize>(&c[3]),
-        utils::parse::<usize>(&c[4]),
-        0,
-        0,
-    ]
+fn parse_registers(registers: &str) -> Result<Registers> {
+    match utils::ints(registers)?[..] {
+        [a, b, c, d] => Ok([a, b, c, d, 0, 0]),
+        _ => Err(Error::new(format!("invalid registers `{}`", registers))),
+    }
 }
 
-fn parse_raw_instruction(instruction: &String) -> [usize; 4] {
+fn parse_raw_instruction(instruction: &str) -> Result<[usize; 4]> {
     let i = instruction
         .split(' ')
-        .map(|n| utils::parse::<usize>(&n))
-        .collect_vec();
-    [i[0], i[1], i[2], i[3]]
+        .map(utils::parse::<usize>)
+        .collect::<Result<Vec<_>>>()?;
+    match i[..] {
+        [op, a, b, c] if a < REGISTER_COUNT && b < REGISTER_COUNT && c < REGISTER_COUNT => {
+            Ok([op, a, b, c])
+        }
+        _ => Err(Error::new(format!("invalid instruction `{}`", instruction))),
+    }
 }
 
-fn parse_instruction(instruction: &String, op_codes: &HashMap<usize, Op>) -> Instruction {
-    let i = instruction
-        .split(' ')
-        .map(|n| utils::parse::<usize>(&n))
-        .collect_vec();
-    Instruction {
-        op: op_codes[&i[0]],
-        data: InstructionData {
-            a: i[1],
-            b: i[2],
-            c: i[3],
-        },
-    }
+fn parse_instruction(instruction: &str, op_codes: &HashMap<usize, Op>) -> Result<Instruction> {
+    let [code, a, b, c] = parse_raw_instruction(instruction)?;
+    let op = *op_codes
+        .get(&code)
+        .ok_or_else(|| Error::new(format!("couldn't deduce op code {}", code)))?;
+    Instruction::new(op, InstructionData { a, b, c })
 }
 
 fn test_ops(before: &Registers, instruction: &[usize; 4], after: &Registers) -> Vec<Op> {
     Op::iter()
-        .map(|op| (op, before.clone()))
+        .map(|op| (op, *before))
         .map(|(op, mut r)| {
             op.op(
                 &mut r,
@@ -111,7 +137,7 @@ fn test_ops(before: &Registers, instruction: &[usize; 4], after: &Registers) ->
         .collect_vec()
 }
 
-fn deduce_op_codes(samples: &mut Vec<(usize, Vec<Op>)>) -> HashMap<usize, Op> {
+fn deduce_op_codes(samples: &mut [Sample]) -> HashMap<usize, Op> {
     let mut op_codes = HashMap::new();
 
     loop {
@@ -133,9 +159,8 @@ fn deduce_op_codes(samples: &mut Vec<(usize, Vec<Op>)>) -> HashMap<usize, Op> {
                     if entry.0 == op_code {
                         entry.1 = Vec::new();
                     } else {
-                        let idx = entry.1.iter().position(|&o| o == op);
-                        if idx.is_some() {
-                            entry.1.remove(idx.unwrap());
+                        if let Some(idx) = entry.1.iter().position(|&o| o == op) {
+                            entry.1.remove(idx);
                         }
                     }
                 }
@@ -146,3 +171,43 @@ fn deduce_op_codes(samples: &mut Vec<(usize, Vec<Op>)>) -> HashMap<usize, Op> {
 
     op_codes
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    #[test]
+    fn part_a_example() {
+        let input = [
+            "Before: [3, 2, 1, 1]",
+            "9 2 1 2",
+            "After:  [3, 2, 2, 1]",
+            "",
+            "",
+            "",
+            "9 2 1 2",
+        ];
+        assert_eq!(run_example::<Day16>(Part::A, &input), 1.into());
+        assert_eq!(
+            test_ops(&[3, 2, 1, 1, 0, 0], &[9, 2, 1, 2], &[3, 2, 2, 1, 0, 0]),
+            vec![Op::addi, Op::mulr, Op::seti]
+        );
+    }
+
+    // There's no example for part b, so check that op codes are deduced by elimination.
+    #[test]
+    fn part_b_deduces_op_codes() {
+        let mut samples = vec![
+            (7, vec![Op::addi, Op::mulr, Op::seti]),
+            (3, vec![Op::mulr]),
+            (5, vec![Op::mulr, Op::seti]),
+        ];
+        let op_codes = deduce_op_codes(&mut samples);
+
+        assert_eq!(op_codes.len(), 3);
+        assert_eq!(op_codes[&3], Op::mulr);
+        assert_eq!(op_codes[&5], Op::seti);
+        assert_eq!(op_codes[&7], Op::addi);
+    }
+}
@@ -1,32 +1,87 @@
-use crate::utils;
-use regex::Regex;
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+use crate::utils::{Grid, Input, Point, Vec2};
 use std::collections::HashSet;
 
-pub fn day17(lines: &mut Vec<String>) {
-    println!("Running Day 17 - a");
+#[derive(Default)]
+pub struct Day17 {
+    ground: Map,
+    flowed: bool,
+}
+
+impl Solver for Day17 {
+    fn day(&self) -> u32 {
+        17
+    }
+
+    fn title(&self) -> &'static str {
+        "Reservoir Research"
+    }
+
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        self.ground = parse_ground(input)?;
+        Ok(())
+    }
+
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        let count = self
+            .ground()
+            .tiles
+            .iter()
+            .filter(|g| g.was_visited())
+            .count();
+
+        Ok(count.into())
+    }
+
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        let count = self.ground().tiles.iter().filter(|g| g.is_water()).count();
 
-    let rex = Regex::new(r"([xy])=(\d+), [xy]=(\d+)..(\d+)").unwrap();
+        Ok(count.into())
+    }
+}
+
+impl Day17 {
+    // Part a counts every wet tile and part b only the standing water, after the same flow.
+    fn ground(&mut self) -> &Map {
+        if !self.flowed {
+            flow_water(&mut self.ground);
+            self.flowed = true;
+        }
+        &self.ground
+    }
+}
+
+// Scans the clay veins into an otherwise dry map.
+fn parse_ground(input: &Input) -> Result<Map> {
+    let veins: Vec<(char, usize, char, usize, usize)> =
+        input.regex_rows(r"^([xy])=(\d+), ([xy])=(\d+)\.\.(\d+)$")?;
     let mut clay = HashSet::new();
-    for line in lines.iter() {
-        let c = rex.captures(line).unwrap();
-        let points: Vec<Point> = match &c[1] {
-            "x" => (utils::parse(&c[3])..utils::parse::<usize>(&c[4]) + 1)
-                .map(|y| (utils::parse(&c[2]), y))
-                .collect(),
-            "y" => (utils::parse(&c[3])..utils::parse::<usize>(&c[4]) + 1)
-                .map(|x| (x, utils::parse(&c[2])))
-                .collect(),
-            _ => panic!(),
+    for (idx, (axis, at, other, from, to)) in veins.into_iter().enumerate() {
+        let range = from..to + 1;
+        let points: Vec<Point> = match (axis, other) {
+            ('x', 'y') => range.map(|y| Vec2::new(at, y)).collect(),
+            ('y', 'x') => range.map(|x| Vec2::new(x, at)).collect(),
+            _ => return Err(input.error_at(idx, "expected a vein along one axis")),
         };
         for p in points {
             clay.insert(p);
         }
     }
 
-    let mut ground = Map::new(clay);
+    // Water flows sideways past the outermost clay, so keep a column spare on the left.
+    if clay.is_empty() || clay.iter().any(|c| c.x == 0) {
+        return Err(Error::new("expected clay veins right of x=0"));
+    }
+
+    Ok(Map::new(clay))
+}
 
+// Lets water flow from the spring until it comes to rest.
+fn flow_water(ground: &mut Map) {
     let mut stack: Vec<Point> = Vec::new();
-    stack.push((500, ground.y_bounds.0));
+    stack.push(Vec2::new(500, ground.y_bounds.0));
 
     while let Some(drop) = stack.pop() {
         stack.push(drop); // peek
@@ -35,26 +90,24 @@ pub fn day17(lines: &mut Vec<String>) {
         }
         let mut queued = false;
         if ground[drop].is_wet() {
-            match ground[(drop.0, drop.1 + 1)] {
-                GroundState::Clay | GroundState::Water => {
-                    if should_settle(&ground, drop) {
-                        settle_row(&mut ground, drop);
-                    }
+            match ground[drop.down()] {
+                GroundState::Clay | GroundState::Water if should_settle(ground, drop) => {
+                    settle_row(ground, drop);
                 }
                 GroundState::Dry => {
-                    stack.push((drop.0, drop.1 + 1));
+                    stack.push(drop.down());
                     queued = true;
                 }
                 _ => {}
             }
 
-            if !queued && ground[(drop.0, drop.1 + 1)].is_resting() {
-                if ground[(drop.0 - 1, drop.1)].is_dry() {
-                    stack.push((drop.0 - 1, drop.1));
+            if !queued && ground[drop.down()].is_resting() {
+                if ground[drop.left()].is_dry() {
+                    stack.push(drop.left());
                     queued = true;
                 }
-                if ground[(drop.0 + 1, drop.1)].is_dry() {
-                    stack.push((drop.0 + 1, drop.1));
+                if ground[drop.right()].is_dry() {
+                    stack.push(drop.right());
                     queued = true;
                 }
             }
@@ -63,26 +116,6 @@ pub fn day17(lines: &mut Vec<String>) {
             stack.pop();
         }
     }
-
-    let count = ground
-        .tiles
-        .iter()
-        .flatten()
-        .filter(|g| g.was_visited())
-        .count();
-
-    println!("Tiles Reached = {}", count);
-
-    println!("Running Day 17 - b");
-
-    let count = ground
-        .tiles
-        .iter()
-        .flatten()
-        .filter(|g| g.is_water())
-        .count();
-
-    println!("Retained Water = {}", count);
 }
 
 fn should_settle(ground: &Map, p: Point) -> bool {
@@ -91,40 +124,38 @@ fn should_settle(ground: &Map, p: Point) -> bool {
 
 fn clay_left(ground: &Map, p: Point) -> bool {
     resting_below(ground, p)
-        && match ground[(p.0 - 1, p.1)] {
+        && match ground[p.left()] {
             GroundState::Clay => true,
             GroundState::OutOfBounds => false,
-            _ => clay_left(ground, (p.0 - 1, p.1)),
+            _ => clay_left(ground, p.left()),
         }
 }
 
 fn clay_right(ground: &Map, p: Point) -> bool {
     resting_below(ground, p)
-        && match ground[(p.0 + 1, p.1)] {
+        && match ground[p.right()] {
             GroundState::Clay => true,
             GroundState::OutOfBounds => false,
-            _ => clay_right(ground, (p.0 + 1, p.1)),
+            _ => clay_right(ground, p.right()),
         }
 }
 
 fn resting_below(ground: &Map, p: Point) -> bool {
-    ground[(p.0, p.1 + 1)].is_resting()
+    ground[p.down()].is_resting()
 }
 
 fn settle_row(ground: &mut Map, p: Point) {
     if ground[p].can_settle() {
         ground[p] = GroundState::Water;
     }
-    if ground[(p.0 - 1, p.1)].can_settle() {
-        settle_row(ground, (p.0 - 1, p.1));
+    if ground[p.left()].can_settle() {
+        settle_row(ground, p.left());
     }
-    if ground[(p.0 + 1, p.1)].can_settle() {
-        settle_row(ground, (p.0 + 1, p.1));
+    if ground[p.right()].can_settle() {
+        settle_row(ground, p.right());
     }
 }
 
-type Point = (usize, usize);
-
 #[derive(Clone)]
 enum GroundState {
     Dry,
@@ -136,62 +167,50 @@ enum GroundState {
 
 impl GroundState {
     fn is_dry(&self) -> bool {
-        match self {
-            GroundState::Dry => true,
-            _ => false,
-        }
+        matches!(self, GroundState::Dry)
     }
 
     fn is_wet(&self) -> bool {
-        match self {
-            GroundState::Wet => true,
-            _ => false,
-        }
+        matches!(self, GroundState::Wet)
     }
 
     fn is_water(&self) -> bool {
-        match self {
-            GroundState::Water => true,
-            _ => false,
-        }
+        matches!(self, GroundState::Water)
     }
 
     fn was_visited(&self) -> bool {
-        match self {
-            GroundState::Wet | GroundState::Water => true,
-            _ => false,
-        }
+        matches!(self, GroundState::Wet | GroundState::Water)
     }
 
     fn is_resting(&self) -> bool {
-        match self {
-            GroundState::Water | GroundState::Clay => true,
-            _ => false,
-        }
+        matches!(self, GroundState::Water | GroundState::Clay)
     }
 
     fn can_settle(&self) -> bool {
-        match self {
-            GroundState::Dry | GroundState::Wet => true,
-            _ => false,
-        }
+        matches!(self, GroundState::Dry | GroundState::Wet)
     }
+}
 
-    fn _to_char(&self) -> char {
-        match self {
+impl std::fmt::Display for GroundState {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let c = match self {
             GroundState::Dry => '.',
             GroundState::Wet => '|',
             GroundState::Water => '~',
             GroundState::Clay => '#',
-            _ => ' ',
-        }
+            GroundState::OutOfBounds => ' ',
+        };
+        write!(f, "{}", c)
     }
 }
 
+// The ground between the outermost clay, with a column spare on either side.
+// Points are in scan coordinates, and anything outside the map is out of bounds.
+#[derive(Default)]
 struct Map {
-    tiles: Vec<Vec<GroundState>>,
-    x_bounds: Point,
-    y_bounds: Point,
+    tiles: Grid<GroundState>,
+    x_bounds: (usize, usize),
+    y_bounds: (usize, usize),
 }
 
 impl Map {
@@ -201,20 +220,21 @@ impl Map {
     {
         let clay: Vec<Point> = clay.into_iter().collect();
         let x_bounds = (
-            clay.iter().map(|c| c.0).min().unwrap() - 1,
-            clay.iter().map(|c| c.0).max().unwrap() + 1,
+            clay.iter().map(|c| c.x).min().unwrap() - 1,
+            clay.iter().map(|c| c.x).max().unwrap() + 1,
         );
         let y_bounds = (
-            clay.iter().map(|c| c.1).min().unwrap(),
-            clay.iter().map(|c| c.1).max().unwrap(),
+            clay.iter().map(|c| c.y).min().unwrap(),
+            clay.iter().map(|c| c.y).max().unwrap(),
         );
         let mut map = Map {
-            tiles: vec![
-                vec![GroundState::Dry; y_bounds.1 - y_bounds.0 + 1];
-                x_bounds.1 - x_bounds.0 + 1
-            ],
-            x_bounds: x_bounds,
-            y_bounds: y_bounds,
+            tiles: Grid::new(
+                x_bounds.1 - x_bounds.0 + 1,
+                y_bounds.1 - y_bounds.0 + 1,
+                GroundState::Dry,
+            ),
+            x_bounds,
+            y_bounds,
         };
 
         for p in clay {
@@ -229,32 +249,53 @@ impl std::ops::Index<Point> for Map {
     type Output = GroundState;
 
     fn index(&self, i: Point) -> &GroundState {
-        if self.x_bounds.0 <= i.0
-            && i.0 <= self.x_bounds.1
-            && self.y_bounds.0 <= i.1
-            && i.1 <= self.y_bounds.1
-        {
-            &self.tiles[i.0 - self.x_bounds.0][i.1 - self.y_bounds.0]
-        } else {
-            &GroundState::OutOfBounds
-        }
+        self.tile_at(i)
+            .and_then(|p| self.tiles.get(p))
+            .unwrap_or(&GroundState::OutOfBounds)
     }
 }
 
 impl std::ops::IndexMut<Point> for Map {
     fn index_mut(&mut self, i: Point) -> &mut GroundState {
-        &mut self.tiles[i.0 - self.x_bounds.0][i.1 - self.y_bounds.0]
+        let p = self.tile_at(i).unwrap();
+        &mut self.tiles[p]
     }
 }
 
 impl Map {
+    // Where a point in scan coordinates is on the tiles.
+    fn tile_at(&self, i: Point) -> Option<Point> {
+        i.checked_sub(Vec2::new(self.x_bounds.0, self.y_bounds.0))
+    }
+
     fn _print(&self) {
-        for y in 0..(self.y_bounds.1 - self.y_bounds.0 + 1) {
-            for x in 0..(self.x_bounds.1 - self.x_bounds.0 + 1) {
-                print!("{}", self.tiles[x][y]._to_char());
-            }
-            println!();
-        }
-        println!();
+        println!("{}", self.tiles);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    const SCAN: [&str; 8] = [
+        "x=495, y=2..7",
+        "y=7, x=495..501",
+        "x=501, y=3..7",
+        "x=498, y=2..4",
+        "x=506, y=1..2",
+        "x=498, y=10..13",
+        "x=504, y=10..13",
+        "y=13, x=498..504",
+    ];
+
+    #[test]
+    fn part_a_example() {
+        assert_eq!(run_example::<Day17>(Part::A, &SCAN), 57.into());
+    }
+
+    #[test]
+    fn part_b_example() {
+        assert_eq!(run_example::<Day17>(Part::B, &SCAN), 29.into());
     }
 }
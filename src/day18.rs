@@ -1,193 +1,175 @@
-use std::collections::HashMap;
-
-pub fn day18(lines: &mut Vec<String>) {
-    println!("Running Day 18 - a");
-
-    // Parse the map, then make a copy of it.
-    // When processing a generation, we swap which map we're reading from and writing to.
-    let mut map = lines
-        .iter()
-        .map(|l| l.chars().map(Acre::from_char).collect())
-        .collect::<Vec<Vec<Acre>>>();
-    let mut map2 = map.clone();
+use crate::answer::Answer;
+use crate::error::Result;
+use crate::solver::Solver;
+use crate::utils::{Cycle, Grid, Input, Order, Point};
+use std::convert::TryFrom;
+
+#[derive(Default)]
+pub struct Day18 {
+    map: Grid<Acre>,
+    cycle: Option<Cycle<Grid<Acre>>>,
+}
+impl Solver for Day18 {
+    fn day(&self) -> u32 {
+        18
+    }
 
-    // Optimization: Pre-cache coordinates of all valid neighbors for each cell
-    let mut neighbor_coords = Vec::with_capacity(map.len());
-
-    for i in 0..map.len() {
-        neighbor_coords.push(Vec::with_capacity(map[i].len()));
-        for j in 0..map[i].len() as isize {
-            let i = i as isize;
-            let mut neighbors = Vec::new();
-            for (i_off, j_off) in iproduct!(-1..2, -1..2) {
-                let p = (i + i_off, j + j_off);
-                if p.0 != i || p.1 != j {
-                    if 0 <= p.0
-                        && p.0 < map.len() as isize
-                        && 0 <= p.1
-                        && p.1 < map[i as usize].len() as isize
-                    {
-                        neighbors.push((p.0 as usize, p.1 as usize));
-                    }
-                }
-            }
-            neighbor_coords[i as usize].push(neighbors);
-        }
+    fn title(&self) -> &'static str {
+        "Settlers of The North Pole"
     }
 
-    let mut prev_map: &Vec<Vec<Acre>>;
-    let mut cur_map: &mut Vec<Vec<Acre>>;
-    let mut gens_by_value = HashMap::new();
-    let mut value_by_gen = Vec::new();
-    let mut generation = 0;
-    let mut cycle_length = None;
-
-    while cycle_length.is_none() {
-        // Choose maps to read from & write to.
-        let cur = generation % 2;
-        if cur == 0 {
-            prev_map = &map;
-            cur_map = &mut map2;
-        } else {
-            prev_map = &map2;
-            cur_map = &mut map;
-        }
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        self.map = input.grid()?;
+        Ok(())
+    }
 
-        // Process generation.
-        for i in 0..prev_map.len() {
-            for j in 0..prev_map[i].len() {
-                let n_trees = count_acres(&neighbor_coords[i][j], &prev_map, Acre::is_trees);
-                let n_yards = count_acres(&neighbor_coords[i][j], &prev_map, Acre::is_yard);
-                cur_map[i][j] = match prev_map[i][j] {
-                    Acre::Open => {
-                        if n_trees >= 3 {
-                            Acre::Trees
-                        } else {
-                            Acre::Open
-                        }
-                    }
-                    Acre::Trees => {
-                        if n_yards >= 3 {
-                            Acre::Yard
-                        } else {
-                            Acre::Trees
-                        }
-                    }
-                    Acre::Yard => {
-                        if n_yards > 0 && n_trees > 0 {
-                            Acre::Yard
-                        } else {
-                            Acre::Open
-                        }
-                    }
-                };
-            }
-        }
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(self.value_after(10).into())
+    }
 
-        // Track this generation's value.
-        let value = get_value(cur_map);
-        value_by_gen.push(value);
-        let gens = gens_by_value.entry(value).or_insert_with(|| Vec::new());
-        gens.push(generation);
-
-        // Attempt to identify a cycle.
-        // We identify a cycle as when the map has the same value three times and the generations lapsed between them match.
-        if gens.len() >= 3 {
-            let end = gens.len() - 1;
-            if gens[end] - gens[end - 1] == gens[end - 1] - gens[end - 2] {
-                cycle_length = Some(gens[end] - gens[end - 1]);
-            }
-        }
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(self.value_after(1_000_000_000).into())
+    }
+}
 
-        generation += 1;
+impl Day18 {
+    // The 10 minutes of part a and the billion of part b both come out of one cycle.
+    fn cycle(&mut self) -> &Cycle<Grid<Acre>> {
+        let map = &self.map;
+        self.cycle.get_or_insert_with(|| find_cycle(map))
     }
 
-    println!(
-        "Total Resource Value after 10 minutes = {}",
-        value_by_gen[10]
-    );
+    // The resource value after a number of minutes.
+    fn value_after(&mut self, minutes: usize) -> usize {
+        get_value(self.cycle().state_at(minutes))
+    }
+}
 
-    println!("Running Day 18 - b");
+// Simulates minutes until the whole area repeats itself.
+fn find_cycle(map: &Grid<Acre>) -> Cycle<Grid<Acre>> {
+    // Optimization: Pre-cache coordinates of all valid neighbors for each cell
+    let neighbor_coords: Grid<Vec<Point>> = Grid::from_fn(map.width(), map.height(), |p| {
+        map.neighbors8(p, Order::Reading).collect()
+    });
 
-    // Using what we know about the cycle length and cycle start,
-    // find the correct value in the cycle for the 1 billionth generation.
-    let cycle_start = generation - cycle_length.unwrap();
-    let cycle_offset = (1_000_000_000 - cycle_start) % cycle_length.unwrap() - 1;
+    Cycle::find(map.clone(), |map| simulate_minute(map, &neighbor_coords))
+}
 
-    println!(
-        "Total Resource Value after 1,000,000,000 minutes = {}",
-        value_by_gen[cycle_start + cycle_offset]
-    );
+fn simulate_minute(prev_map: &Grid<Acre>, neighbor_coords: &Grid<Vec<Point>>) -> Grid<Acre> {
+    let mut cur_map = prev_map.clone();
+    for p in prev_map.points() {
+        let n_trees = count_acres(&neighbor_coords[p], prev_map, Acre::is_trees);
+        let n_yards = count_acres(&neighbor_coords[p], prev_map, Acre::is_yard);
+        cur_map[p] = match prev_map[p] {
+            Acre::Open => {
+                if n_trees >= 3 {
+                    Acre::Trees
+                } else {
+                    Acre::Open
+                }
+            }
+            Acre::Trees => {
+                if n_yards >= 3 {
+                    Acre::Yard
+                } else {
+                    Acre::Trees
+                }
+            }
+            Acre::Yard => {
+                if n_yards > 0 && n_trees > 0 {
+                    Acre::Yard
+                } else {
+                    Acre::Open
+                }
+            }
+        };
+    }
+    cur_map
 }
 
-fn get_value(map: &Vec<Vec<Acre>>) -> usize {
+fn get_value(map: &Grid<Acre>) -> usize {
     count_map_acres(map, Acre::is_trees) * count_map_acres(map, Acre::is_yard)
 }
 
-fn count_map_acres(map: &Vec<Vec<Acre>>, comp: fn(&Acre) -> bool) -> usize {
-    map.iter()
-        .flat_map(|l| l.iter())
-        .filter(|a| comp(a))
-        .count()
+fn count_map_acres(map: &Grid<Acre>, comp: fn(&Acre) -> bool) -> usize {
+    map.iter().filter(|a| comp(a)).count()
 }
 
-fn count_acres(
-    n: &Vec<(usize, usize)>,
-    map: &Vec<Vec<Acre>>,
-    comparison: fn(&Acre) -> bool,
-) -> usize {
-    n.iter()
-        .map(|p| &map[p.0][p.1])
-        .filter(|a| comparison(a))
-        .count()
+fn count_acres(n: &[Point], map: &Grid<Acre>, comparison: fn(&Acre) -> bool) -> usize {
+    n.iter().map(|&p| &map[p]).filter(|a| comparison(a)).count()
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 enum Acre {
     Open,
     Trees,
     Yard,
 }
 
-impl Acre {
-    fn from_char(c: char) -> Self {
+impl TryFrom<char> for Acre {
+    type Error = char;
+
+    fn try_from(c: char) -> std::result::Result<Self, char> {
         match c {
-            '.' => Acre::Open,
-            '|' => Acre::Trees,
-            '#' => Acre::Yard,
-            _ => panic!(),
+            '.' => Ok(Acre::Open),
+            '|' => Ok(Acre::Trees),
+            '#' => Ok(Acre::Yard),
+            _ => Err(c),
         }
     }
+}
 
-    fn _to_char(&self) -> char {
-        match self {
+impl std::fmt::Display for Acre {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let c = match self {
             Acre::Open => '.',
             Acre::Trees => '|',
             Acre::Yard => '#',
-        }
+        };
+        write!(f, "{}", c)
     }
+}
 
+impl Acre {
     fn is_trees(&self) -> bool {
-        match self {
-            Acre::Trees => true,
-            _ => false,
-        }
+        matches!(self, Acre::Trees)
     }
 
     fn is_yard(&self) -> bool {
-        match self {
-            Acre::Yard => true,
-            _ => false,
-        }
+        matches!(self, Acre::Yard)
     }
 }
 
-fn _print(map: &Vec<Vec<Acre>>) {
-    for i in 0..map.len() {
-        for j in 0..map[i].len() {
-            print!("{}", map[i][j]._to_char());
-        }
-        println!();
+fn _print(map: &Grid<Acre>) {
+    println!("{}", map);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    const AREA: [&str; 10] = [
+        ".#.#...|#.",
+        ".....#|##|",
+        ".|..|...#.",
+        "..|#.....#",
+        "#.#|||#|#|",
+        "...#.||...",
+        ".|....|...",
+        "||...#|.#|",
+        "|.||||..|.",
+        "...#.|..|.",
+    ];
+
+    #[test]
+    fn part_a_example() {
+        assert_eq!(run_example::<Day18>(Part::A, &AREA), 1147.into());
+    }
+
+    // The example has no answer for part b, but its forest dies out after 17 minutes.
+    #[test]
+    fn part_b_example() {
+        assert_eq!(run_example::<Day18>(Part::B, &AREA), 0.into());
     }
-    println!();
 }
@@ -1,26 +1,54 @@
+use crate::answer::Answer;
+use crate::cancel;
 use crate::emulator::{Emulator, Program};
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+use crate::utils::Input;
 
-pub fn day19(lines: &mut Vec<String>) {
-    println!("Running Day 19 - a");
+#[derive(Default)]
+pub struct Day19 {
+    program: Option<Program>,
+}
+
+impl Solver for Day19 {
+    fn day(&self) -> u32 {
+        19
+    }
+
+    fn title(&self) -> &'static str {
+        "Go With The Flow"
+    }
 
-    let program = Program::new(lines);
-    let mut emulator = Emulator::new();
-    emulator.run_program(&program);
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        self.program = Some(Program::new(input.lines())?);
+        Ok(())
+    }
 
-    println!("Value of Register 0 = {}", emulator.registers[0]);
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        let program = self.program.as_ref().unwrap();
+        let mut emulator = Emulator::new();
+        emulator.run_program(program)?;
 
-    println!("Running Day 19 - b");
+        Ok(emulator.registers[0].into())
+    }
 
-    emulator = Emulator::new();
-    emulator.registers[0] = 1;
-    run_program_b(&mut emulator, &program);
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        let program = self.program.as_ref().unwrap();
+        let mut emulator = Emulator::new();
+        emulator.registers[0] = 1;
+        run_program_b(&mut emulator, program)?;
 
-    println!("Value of Register 0 = {}", emulator.registers[0]);
+        Ok(emulator.registers[0].into())
+    }
 }
 
-fn run_program_b(emulator: &mut Emulator, program: &Program) {
+fn run_program_b(emulator: &mut Emulator, program: &Program) -> Result<()> {
     // Stop executing instructions once we enter the core calculation loop.
     while emulator.ip != 1 {
+        if emulator.ip >= program.instructions.len() {
+            return Err(Error::new("the program halted before its calculation loop"));
+        }
+        cancel::check()?;
         emulator.run_instruction(program);
     }
     // The program is iterating all pairs of numbers less than or equal to a value stored
@@ -29,9 +57,62 @@ fn run_program_b(emulator: &mut Emulator, program: &Program) {
     // In essence, it's summing the divisors.
     let mut sum = 0;
     for i in 1..emulator.registers[4] + 1 {
-        if emulator.registers[4] % i == 0 {
+        if emulator.registers[4].is_multiple_of(i) {
             sum += i;
         }
     }
     emulator.registers[0] = sum;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    #[test]
+    fn part_a_example() {
+        let program = [
+            "#ip 0",
+            "seti 5 0 1",
+            "seti 6 0 2",
+            "addi 0 1 0",
+            "addr 1 2 3",
+            "setr 1 0 0",
+            "seti 8 0 4",
+            "seti 9 0 5",
+        ];
+        assert_eq!(run_example::<Day19>(Part::A, &program), 6.into());
+    }
+
+    // Part b only works on programs shaped like the puzzle input, and there's no example of one.
+    // This is the calculation loop from an input, set up to sum the divisors of 12,
+    // so that running it in full has to agree with the shortcut.
+    #[test]
+    fn part_b_sums_divisors() {
+        let program = [
+            "#ip 1",
+            "addi 1 16 1",
+            "seti 1 5 5",
+            "seti 1 2 3",
+            "mulr 5 3 2",
+            "eqrr 2 4 2",
+            "addr 2 1 1",
+            "addi 1 1 1",
+            "addr 5 0 0",
+            "addi 3 1 3",
+            "gtrr 3 4 2",
+            "addr 1 2 1",
+            "seti 2 6 1",
+            "addi 5 1 5",
+            "gtrr 5 4 2",
+            "addr 2 1 1",
+            "seti 1 8 1",
+            "mulr 1 1 1",
+            "seti 12 0 4",
+            "seti 0 0 1",
+        ];
+        assert_eq!(run_example::<Day19>(Part::A, &program), 28.into());
+        assert_eq!(run_example::<Day19>(Part::B, &program), 28.into());
+    }
 }
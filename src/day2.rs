@@ -1,67 +1,120 @@
-pub fn day2(lines: &mut Vec<String>) {
-    println!("Running Day 2 - a");
+use crate::answer::Answer;
+use crate::error::{Error, LineContext, Result};
+use crate::solver::Solver;
+use crate::utils::Input;
 
-    let mut twoCount = 0;
-    let mut threeCount = 0;
+#[derive(Default)]
+pub struct Day2;
 
-    for line in lines.iter() {
-        let mut counts = vec![0; 26];
+impl Solver for Day2 {
+    fn day(&self) -> u32 {
+        2
+    }
 
-        for c in line.chars() {
-            let c = (c as usize) - 97;
-            counts[c] += 1;
-        }
+    fn title(&self) -> &'static str {
+        "Inventory Management System"
+    }
 
-        let mut twoFound = false;
-        let mut threeFound = false;
+    fn part_a(&mut self, input: &Input) -> Result<Answer> {
+        let mut twoCount = 0;
+        let mut threeCount = 0;
 
-        for count in counts.iter() {
-            if !twoFound && *count == 2 {
-                twoCount += 1;
-                twoFound = true;
-            }
+        for (idx, line) in input.lines().iter().enumerate() {
+            let counts = count_letters(line).line(idx)?;
+
+            let mut twoFound = false;
+            let mut threeFound = false;
+
+            for count in counts.iter() {
+                if !twoFound && *count == 2 {
+                    twoCount += 1;
+                    twoFound = true;
+                }
 
-            if !threeFound && *count == 3 {
-                threeCount += 1;
-                threeFound = true;
+                if !threeFound && *count == 3 {
+                    threeCount += 1;
+                    threeFound = true;
+                }
             }
         }
-    }
 
-    println!("Checksum = {}", twoCount * threeCount);
-    println!("Running Day 2 - b");
+        Ok((twoCount * threeCount).into())
+    }
 
-    for idxA in 0..lines.len() {
-        for idxB in idxA + 1..lines.len() {
-            let mut mismatchIdx = Option::None;
-            let mut completeMismatch = false;
+    fn part_b(&mut self, input: &Input) -> Result<Answer> {
+        let lines = input.lines();
+        for idxA in 0..lines.len() {
+            for idxB in idxA + 1..lines.len() {
+                let mut mismatchIdx = Option::None;
+                let mut completeMismatch = false;
 
-            let lineA = &lines[idxA];
-            let lineB = &lines[idxB];
+                let lineA = &lines[idxA];
+                let lineB = &lines[idxB];
 
-            let charsIter = lineA.chars().zip(lineB.chars());
+                let charsIter = lineA.chars().zip(lineB.chars());
 
-            for (idx, (charA, charB)) in charsIter.enumerate() {
-                if charA != charB {
-                    match mismatchIdx {
-                        None => mismatchIdx = Some(idx),
-                        Some(_) => completeMismatch = true,
+                for (idx, (charA, charB)) in charsIter.enumerate() {
+                    if charA != charB {
+                        match mismatchIdx {
+                            None => mismatchIdx = Some(idx),
+                            Some(_) => completeMismatch = true,
+                        }
                     }
                 }
-            }
 
-            if completeMismatch {
-                continue;
-            }
+                if completeMismatch {
+                    continue;
+                }
 
-            match mismatchIdx {
-                None => continue,
-                Some(idx) => {
-                    // let lineC = format!("{}{}", &lineA[0..idx], &lineA[idx + 1..]);
-                    let lineC = [&lineA[0..idx], &lineA[idx + 1..]].concat();
-                    println!("Common Letters = {}", lineC);
+                match mismatchIdx {
+                    None => continue,
+                    Some(idx) => {
+                        // let lineC = format!("{}{}", &lineA[0..idx], &lineA[idx + 1..]);
+                        let lineC = [&lineA[0..idx], &lineA[idx + 1..]].concat();
+                        return Ok(lineC.into());
+                    }
                 }
             }
         }
+
+        Err(Error::new("no two box IDs differ by exactly one character"))
+    }
+}
+
+fn count_letters(id: &str) -> Result<[u32; 26]> {
+    let mut counts = [0; 26];
+
+    for c in id.chars() {
+        if !c.is_ascii_lowercase() {
+            return Err(Error::new(format!(
+                "unexpected character `{}` in box ID",
+                c
+            )));
+        }
+        counts[(c as usize) - 97] += 1;
+    }
+
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    #[test]
+    fn part_a_example() {
+        let ids = [
+            "abcdef", "bababc", "abbcde", "abcccd", "aabcdd", "abcdee", "ababab",
+        ];
+        assert_eq!(run_example::<Day2>(Part::A, &ids), 12.into());
+    }
+
+    #[test]
+    fn part_b_example() {
+        let ids = [
+            "abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz",
+        ];
+        assert_eq!(run_example::<Day2>(Part::B, &ids), "fgij".into());
     }
 }
@@ -1,49 +1,107 @@
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+use crate::utils::{self, search, Direction, Input, Vec2};
 use std::collections::HashSet;
-use std::collections::VecDeque;
 
-pub fn day20(lines: &mut Vec<String>) {
-    println!("Running Day 20 - a");
+pub struct Day20 {
+    tokens: Vec<Token>,
+    rooms: Option<BfsMap>,
+    // Part b counts the rooms that are at least this many doors away.
+    min_doors: usize,
+}
 
-    let mut doors = HashSet::new();
-    let tokens = lines[0].chars().filter_map(|c| Token::parse(c)).collect();
+impl Default for Day20 {
+    fn default() -> Self {
+        Day20 {
+            tokens: Vec::new(),
+            rooms: None,
+            min_doors: 1000,
+        }
+    }
+}
 
-    branch(&tokens, 0, (0, 0), &mut doors);
+impl Solver for Day20 {
+    fn day(&self) -> u32 {
+        20
+    }
 
-    let (doors, start) = normalize(&doors);
-    let bfs_map = build_bfs(&doors, start);
+    fn title(&self) -> &'static str {
+        "A Regular Map"
+    }
 
-    let max_bfs = bfs_map
-        .iter()
-        .flat_map(|l| l.iter().filter_map(|&d| d))
-        .max()
-        .unwrap();
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        let route = input
+            .single_line()?
+            .strip_prefix('^')
+            .and_then(|r| r.strip_suffix('$'))
+            .ok_or_else(|| Error::at_line(0, "expected the route to be wrapped in `^...$`"))?;
+
+        let mut depth = 0;
+        self.tokens = Vec::with_capacity(route.len());
+        for c in route.chars() {
+            let token = Token::parse(c)
+                .ok_or_else(|| Error::at_line(0, format!("invalid route character `{}`", c)))?;
+            match token {
+                Token::GroupStart => depth += 1,
+                Token::GroupEnd if depth == 0 => return Err(Error::at_line(0, "unbalanced `)`")),
+                Token::GroupEnd => depth -= 1,
+                _ => {}
+            }
+            self.tokens.push(token);
+        }
+        if depth != 0 {
+            return Err(Error::at_line(0, "unbalanced `(`"));
+        }
+        if !self.tokens.iter().any(|t| matches!(t, Token::Direction(_))) {
+            return Err(Error::at_line(0, "the route doesn't go through any doors"));
+        }
+        Ok(())
+    }
 
-    println!("Distance to Furthest Room = {}", max_bfs);
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        let bfs_map = self.rooms();
 
-    println!("Running Day 20 - b");
+        let max_bfs = bfs_map.distances().max().unwrap();
 
-    let count = bfs_map
-        .iter()
-        .flat_map(|l| l.iter().filter_map(|&d| d))
-        .filter(|&d| d >= 1000)
-        .count();
+        Ok(max_bfs.into())
+    }
 
-    println!("Rooms with Distance >= 1000 = {}", count);
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        let min_doors = self.min_doors;
+        let bfs_map = self.rooms();
+
+        let count = bfs_map.distances().filter(|&d| d >= min_doors).count();
+
+        Ok(count.into())
+    }
 }
 
-fn branch(
-    tokens: &Vec<Token>,
-    mut cursor: usize,
-    start_p: Point,
-    doors: &mut HashSet<Door>,
-) -> usize {
-    let mut p = (start_p.0, start_p.1);
+impl Day20 {
+    fn rooms(&mut self) -> &BfsMap {
+        let tokens = &self.tokens;
+        self.rooms.get_or_insert_with(|| map_rooms(tokens))
+    }
+}
+
+// Walks the route regex to find every door, then finds the distance to each room.
+fn map_rooms(tokens: &[Token]) -> BfsMap {
+    let mut doors = HashSet::new();
+
+    branch(tokens, 0, Vec2::zero(), &mut doors);
+
+    let (doors, start) = normalize(&doors);
+    build_bfs(&doors, start)
+}
+
+fn branch(tokens: &[Token], mut cursor: usize, start_p: Point, doors: &mut HashSet<Door>) -> usize {
+    let mut p = start_p;
 
     while cursor < tokens.len() {
         match &tokens[cursor] {
             Token::Direction(d) => {
-                let from = (p.0, p.1);
-                p = d.walk(&from);
+                let from = p;
+                p = d.step(from);
                 doors.insert(Door::new(from, p));
                 cursor += 1;
             }
@@ -60,70 +118,44 @@ fn branch(
 }
 
 fn normalize(doors: &HashSet<Door>) -> (HashSet<Door>, Point) {
-    let min_x = doors.iter().map(|d| d.from.0).min().unwrap();
-    let min_y = doors.iter().map(|d| d.from.1).min().unwrap();
+    let min = Vec2::new(
+        doors.iter().map(|d| d.from.x).min().unwrap(),
+        doors.iter().map(|d| d.from.y).min().unwrap(),
+    );
 
     let mut new_doors = HashSet::new();
     for door in doors {
-        new_doors.insert(Door::new(
-            (door.from.0 - min_x, door.from.1 - min_y),
-            (door.to.0 - min_x, door.to.1 - min_y),
-        ));
+        new_doors.insert(Door::new(door.from - min, door.to - min));
     }
 
-    (new_doors, (-min_x, -min_y))
+    (new_doors, -min)
 }
 
 fn build_bfs(doors: &HashSet<Door>, start: Point) -> BfsMap {
-    let max_x = doors.iter().map(|d| d.to.0).max().unwrap() as usize + 1;
-    let max_y = doors.iter().map(|d| d.to.1).max().unwrap() as usize + 1;
-    let mut bfs_map = vec![vec![None; max_y]; max_x];
-
-    let mut queue: VecDeque<(UPoint, usize)> = VecDeque::new();
-
-    let start = (start.0 as usize, start.1 as usize);
-    queue.push_back((start, 0));
-    bfs_map[start.0][start.1] = Some(0);
-
-    while let Some((p, bfs)) = queue.pop_front() {
-        let neighbors = get_neighbors(p, doors);
-        for n in neighbors {
-            if bfs_map[n.0][n.1].is_none() {
-                bfs_map[n.0][n.1] = Some(bfs + 1);
-                queue.push_back((n, bfs + 1));
-            }
-        }
-    }
+    let max_x = doors.iter().map(|d| d.to.x).max().unwrap() as usize + 1;
+    let max_y = doors.iter().map(|d| d.to.y).max().unwrap() as usize + 1;
 
-    bfs_map
+    let start = Vec2::new(start.x as usize, start.y as usize);
+    search::bfs(max_x, max_y, vec![start], |p| get_neighbors(p, doors))
 }
 
-fn get_neighbors(p: UPoint, doors: &HashSet<Door>) -> Vec<UPoint> {
-    let p = (p.0 as isize, p.1 as isize);
-    let mut neighbors = Vec::new();
-    if p.0 > 0 && doors.contains(&Door::new(p, (p.0 - 1, p.1))) {
-        neighbors.push(((p.0 - 1) as usize, p.1 as usize));
-    }
-    if p.1 > 0 && doors.contains(&Door::new(p, (p.0, p.1 - 1))) {
-        neighbors.push((p.0 as usize, (p.1 - 1) as usize));
-    }
-    if doors.contains(&Door::new(p, (p.0 + 1, p.1))) {
-        neighbors.push(((p.0 + 1) as usize, p.1 as usize));
-    }
-    if doors.contains(&Door::new(p, (p.0, p.1 + 1))) {
-        neighbors.push((p.0 as usize, (p.1 + 1) as usize));
-    }
-    neighbors
+fn get_neighbors(p: utils::Point, doors: &HashSet<Door>) -> Vec<utils::Point> {
+    let p = Vec2::new(p.x as isize, p.y as isize);
+    [p.left(), p.up(), p.right(), p.down()]
+        .iter()
+        .filter(|&&n| n.x >= 0 && n.y >= 0 && doors.contains(&Door::new(p, n)))
+        .map(|n| Vec2::new(n.x as usize, n.y as usize))
+        .collect()
 }
 
 fn _print(doors: &HashSet<Door>, p: Point) {
     let x_bounds = (
-        doors.iter().map(|d| d.from.0).min().unwrap(),
-        doors.iter().map(|d| d.to.0).max().unwrap() + 1,
+        doors.iter().map(|d| d.from.x).min().unwrap(),
+        doors.iter().map(|d| d.to.x).max().unwrap() + 1,
     );
     let y_bounds = (
-        doors.iter().map(|d| d.from.1).min().unwrap(),
-        doors.iter().map(|d| d.to.1).max().unwrap() + 1,
+        doors.iter().map(|d| d.from.y).min().unwrap(),
+        doors.iter().map(|d| d.to.y).max().unwrap() + 1,
     );
 
     for _ in x_bounds.0..x_bounds.1 {
@@ -135,19 +167,19 @@ fn _print(doors: &HashSet<Door>, p: Point) {
         print!("#");
         for x in x_bounds.0..x_bounds.1 {
             let mut room = '.';
-            if x == p.0 && y == p.1 {
+            if x == p.x && y == p.y {
                 room = 'X';
             }
-            if doors.contains(&Door::new((x, y), (x + 1, y))) {
+            if doors.contains(&Door::new(Vec2::new(x, y), Vec2::new(x + 1, y))) {
                 print!("{}|", room);
             } else {
                 print!("{}#", room);
             }
         }
-        println!("");
+        println!();
         print!("#");
         for x in x_bounds.0..x_bounds.1 {
-            if doors.contains(&Door::new((x, y), (x, y + 1))) {
+            if doors.contains(&Door::new(Vec2::new(x, y), Vec2::new(x, y + 1))) {
                 print!("-#");
             } else {
                 print!("##");
@@ -172,9 +204,8 @@ impl Door {
     }
 }
 
-type Point = (isize, isize);
-type UPoint = (usize, usize);
-type BfsMap = Vec<Vec<Option<(usize)>>>;
+type Point = Vec2<isize>;
+type BfsMap = search::Bfs;
 
 enum Token {
     Direction(Direction),
@@ -186,39 +217,46 @@ enum Token {
 impl Token {
     fn parse(c: char) -> Option<Token> {
         match c {
-            'N' | 'S' | 'E' | 'W' => Some(Token::Direction(Direction::parse(c))),
             '(' => Some(Token::GroupStart),
             '|' => Some(Token::Or),
             ')' => Some(Token::GroupEnd),
-            _ => None,
+            _ => Direction::from_letter(c).map(Token::Direction),
         }
     }
 }
 
-enum Direction {
-    N,
-    S,
-    E,
-    W,
-}
-
-impl Direction {
-    fn walk(&self, p: &Point) -> Point {
-        match self {
-            Direction::N => (p.0, p.1 - 1),
-            Direction::S => (p.0, p.1 + 1),
-            Direction::E => (p.0 + 1, p.1),
-            Direction::W => (p.0 - 1, p.1),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, run_example_with, Part};
+
+    #[test]
+    fn part_a_examples() {
+        let routes = [
+            ("^WNE$", 3),
+            ("^ENWWW(NEEE|SSE(EE|N))$", 10),
+            ("^ENNWSWW(NEWS|)SSSEEN(WNSE|)EE(SWEN|)NNN$", 18),
+            ("^ESSWWN(E|NNENN(EESS(WNSE|)SSS|WWWSSSSE(SW|NNNE)))$", 23),
+            (
+                "^WSSEESWWWNW(S|NENNEEEENN(ESSSSW(NWSW|SSEN)|WSWWN(E|WWS(E|SS))))$",
+                31,
+            ),
+        ];
+        for &(route, doors) in routes.iter() {
+            assert_eq!(run_example::<Day20>(Part::A, &[route]), doors.into());
         }
     }
 
-    fn parse(c: char) -> Direction {
-        match c {
-            'N' => Direction::N,
-            'S' => Direction::S,
-            'E' => Direction::E,
-            'W' => Direction::W,
-            _ => panic!(),
+    // The puzzle has no example for part b, so count the rooms of an example map by hand.
+    #[test]
+    fn part_b_example() {
+        let route = ["^ENWWW(NEEE|SSE(EE|N))$"];
+        for &(min_doors, rooms) in [(5, 11), (10, 1), (11, 0)].iter() {
+            let day = Day20 {
+                min_doors,
+                ..Day20::default()
+            };
+            assert_eq!(run_example_with(day, Part::B, &route), rooms.into());
         }
     }
 }
@@ -1,5 +1,25 @@
-pub fn day21(lines: &mut Vec<String>) {
-    println!("Running Day 21 - a");
+use crate::answer::Answer;
+use crate::error::Result;
+use crate::solver::Solver;
+use crate::utils::Input;
 
-    println!("Running Day 21 - b");
+#[derive(Default)]
+pub struct Day21;
+
+impl Solver for Day21 {
+    fn day(&self) -> u32 {
+        21
+    }
+
+    fn title(&self) -> &'static str {
+        "Chronal Conversion"
+    }
+
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(Answer::Unsolved)
+    }
+
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(Answer::Unsolved)
+    }
 }
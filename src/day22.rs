@@ -1,5 +1,25 @@
-pub fn day22(lines: &mut Vec<String>) {
-    println!("Running Day 22 - a");
+use crate::answer::Answer;
+use crate::error::Result;
+use crate::solver::Solver;
+use crate::utils::Input;
 
-    println!("Running Day 22 - b");
+#[derive(Default)]
+pub struct Day22;
+
+impl Solver for Day22 {
+    fn day(&self) -> u32 {
+        22
+    }
+
+    fn title(&self) -> &'static str {
+        "Mode Maze"
+    }
+
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(Answer::Unsolved)
+    }
+
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(Answer::Unsolved)
+    }
 }
@@ -1,5 +1,25 @@
-pub fn day23(lines: &mut Vec<String>) {
-    println!("Running Day 23 - a");
+use crate::answer::Answer;
+use crate::error::Result;
+use crate::solver::Solver;
+use crate::utils::Input;
 
-    println!("Running Day 23 - b");
+#[derive(Default)]
+pub struct Day23;
+
+impl Solver for Day23 {
+    fn day(&self) -> u32 {
+        23
+    }
+
+    fn title(&self) -> &'static str {
+        "Experimental Emergency Teleportation"
+    }
+
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(Answer::Unsolved)
+    }
+
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(Answer::Unsolved)
+    }
 }
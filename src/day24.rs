@@ -1,5 +1,25 @@
-pub fn day24(lines: &mut Vec<String>) {
-    println!("Running Day 24 - a");
+use crate::answer::Answer;
+use crate::error::Result;
+use crate::solver::Solver;
+use crate::utils::Input;
 
-    println!("Running Day 24 - b");
+#[derive(Default)]
+pub struct Day24;
+
+impl Solver for Day24 {
+    fn day(&self) -> u32 {
+        24
+    }
+
+    fn title(&self) -> &'static str {
+        "Immune System Simulator 20XX"
+    }
+
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(Answer::Unsolved)
+    }
+
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(Answer::Unsolved)
+    }
 }
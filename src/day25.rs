@@ -1,5 +1,25 @@
-pub fn day25(lines: &mut Vec<String>) {
-    println!("Running Day 25 - a");
+use crate::answer::Answer;
+use crate::error::Result;
+use crate::solver::Solver;
+use crate::utils::Input;
 
-    println!("Running Day 25 - b");
+#[derive(Default)]
+pub struct Day25;
+
+impl Solver for Day25 {
+    fn day(&self) -> u32 {
+        25
+    }
+
+    fn title(&self) -> &'static str {
+        "Four-Dimensional Adventure"
+    }
+
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(Answer::Unsolved)
+    }
+
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(Answer::Unsolved)
+    }
 }
@@ -1,63 +1,94 @@
-use crate::utils;
-use regex::Regex;
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+use crate::utils::{Grid, Input, Point, Vec2};
 
-pub fn day3(lines: &mut Vec<String>) {
-    println!("Running Day 3 - a");
-
-    // Rawr
-    let rex = Regex::new(r"^#(\d+)\s@\s(\d+),(\d+):\s(\d+)x(\d+)$").unwrap();
+#[derive(Default)]
+pub struct Day3 {
+    claims: Vec<Claim>,
+    cloth: Grid<i32>,
+}
 
-    let mut claims: Vec<Claim> = Vec::new();
+impl Solver for Day3 {
+    fn day(&self) -> u32 {
+        3
+    }
 
-    for line in lines.iter() {
-        let cap = rex.captures(line).unwrap();
+    fn title(&self) -> &'static str {
+        "No Matter How You Slice It"
+    }
 
-        claims.push(Claim {
-            id: utils::parse(&cap[1]),
-            x: utils::parse(&cap[2]),
-            y: utils::parse(&cap[3]),
-            w: utils::parse(&cap[4]),
-            h: utils::parse(&cap[5]),
-        });
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        self.claims = parse_claims(input)?;
+        self.cloth = cut_cloth(&self.claims)?;
+        Ok(())
     }
 
-    let mut cloth: Vec<i32> = vec![0; 1000 * 1000];
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        let count = self.cloth.iter().filter(|&x| *x > 1).count();
 
-    for claim in claims.iter() {
-        for x in claim.x..claim.x + claim.w {
-            for y in claim.y..claim.y + claim.h {
-                cloth[(x * 1000 + y) as usize] += 1;
+        Ok(count.into())
+    }
+
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        for claim in self.claims.iter() {
+            let mut unique = true;
+            for p in claim.points() {
+                if self.cloth[p] > 1 {
+                    unique = false;
+                }
+            }
+            if unique {
+                return Ok(claim.id.into());
             }
         }
+
+        Err(Error::new("every claim overlaps another"))
     }
+}
 
-    let count = cloth.iter().filter(|&x| *x > 1).count();
+fn parse_claims(input: &Input) -> Result<Vec<Claim>> {
+    // Rawr
+    let rows = input.regex_rows(r"^#(\d+)\s@\s(\d+),(\d+):\s(\d+)x(\d+)$")?;
 
-    println!("Count = {}", count);
+    Ok(rows
+        .into_iter()
+        .map(|(id, x, y, w, h)| Claim { id, x, y, w, h })
+        .collect())
+}
 
-    println!("Running Day 3 - b");
+fn cut_cloth(claims: &[Claim]) -> Result<Grid<i32>> {
+    let mut cloth = Grid::new(1000, 1000, 0);
 
     for claim in claims.iter() {
-        let mut unique = true;
-        for x in claim.x..claim.x + claim.w {
-            for y in claim.y..claim.y + claim.h {
-                if cloth[(x * 1000 + y) as usize] > 1 {
-                    unique = false;
-                }
-            }
+        if claim.x + claim.w > cloth.width() || claim.y + claim.h > cloth.height() {
+            return Err(Error::new(format!(
+                "claim #{} doesn't fit on the fabric",
+                claim.id
+            )));
         }
-        if unique {
-            println!("Non-Overlapping Claim ID = {}", claim.id);
+
+        for p in claim.points() {
+            cloth[p] += 1;
         }
     }
+
+    Ok(cloth)
 }
 
 struct Claim {
     id: i32,
-    x: i32,
-    y: i32,
-    w: i32,
-    h: i32,
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+}
+
+impl Claim {
+    // Every square inch of fabric the claim covers.
+    fn points(&self) -> impl Iterator<Item = Point> {
+        iproduct!(self.x..self.x + self.w, self.y..self.y + self.h).map(Vec2::from)
+    }
 }
 
 // Unused, kept for example
@@ -70,3 +101,21 @@ impl std::fmt::Display for Claim {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    const CLAIMS: [&str; 3] = ["#1 @ 1,3: 4x4", "#2 @ 3,1: 4x4", "#3 @ 5,5: 2x2"];
+
+    #[test]
+    fn part_a_example() {
+        assert_eq!(run_example::<Day3>(Part::A, &CLAIMS), 4.into());
+    }
+
+    #[test]
+    fn part_b_example() {
+        assert_eq!(run_example::<Day3>(Part::B, &CLAIMS), 3.into());
+    }
+}
@@ -1,11 +1,54 @@
-use crate::utils;
+use crate::answer::Answer;
+use crate::error::{Error, LineContext, Result};
+use crate::solver::Solver;
+use crate::utils::{self, Input};
 use regex::Regex;
 use std::collections::HashMap;
 
-pub fn day4(lines: &mut Vec<String>) {
-    println!("Running Day 4 - a");
+#[derive(Default)]
+pub struct Day4 {
+    guards: HashMap<i32, Guard>,
+}
+
+impl Solver for Day4 {
+    fn day(&self) -> u32 {
+        4
+    }
 
-    lines.sort(); // ... seriously? wow, that was easy.
+    fn title(&self) -> &'static str {
+        "Repose Record"
+    }
+
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        self.guards = track_guards(input.lines())?;
+        Ok(())
+    }
+
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        let sleepiestGuard = self
+            .guards
+            .values()
+            .max_by_key(|g| g.minsAsleep)
+            .ok_or_else(|| Error::new("no guards on duty"))?;
+
+        Ok((sleepiestGuard.id * sleepiestGuard.minMost).into())
+    }
+
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        let predictableGuard = self
+            .guards
+            .values()
+            .max_by_key(|g| g.mostInMin)
+            .ok_or_else(|| Error::new("no guards on duty"))?;
+
+        Ok((predictableGuard.id * predictableGuard.minMost).into())
+    }
+}
+
+fn track_guards(lines: &[String]) -> Result<HashMap<i32, Guard>> {
+    // Keep each record's line number for errors.
+    let mut lines = lines.iter().enumerate().collect::<Vec<_>>();
+    lines.sort_by_key(|&(_, line)| line); // ... seriously? wow, that was easy.
 
     let timeRex = Regex::new(r"\d+:(?P<m>\d+)").unwrap();
     let beginRex = Regex::new(r"Guard #(?P<g>\d+)").unwrap();
@@ -15,17 +58,30 @@ pub fn day4(lines: &mut Vec<String>) {
     let mut guardOnDuty: Option<i32> = Option::None;
     let mut asleep: Option<i32> = Option::None;
 
-    for line in lines.iter() {
+    for (idx, line) in lines {
+        let invalid = || Error::at_line(idx, format!("invalid record `{}`", line));
+
         if line.contains("falls asleep") {
-            let minute = utils::parse(&timeRex.captures(line).unwrap()["m"]);
+            let minute =
+                utils::parse(&timeRex.captures(line).ok_or_else(invalid)?["m"]).line(idx)?;
             asleep = Option::Some(minute);
         } else if line.contains("wakes up") {
-            let minute = utils::parse(&timeRex.captures(line).unwrap()["m"]);
-            let guard = guards.get_mut(&guardOnDuty.unwrap()).unwrap();
-            guard.sleep(asleep.unwrap(), minute);
+            let minute =
+                utils::parse(&timeRex.captures(line).ok_or_else(invalid)?["m"]).line(idx)?;
+            let guard = guardOnDuty
+                .and_then(|id| guards.get_mut(&id))
+                .ok_or_else(|| Error::at_line(idx, "woke up with no guard on duty"))?;
+            let from = asleep
+                .take()
+                .ok_or_else(|| Error::at_line(idx, "woke up without falling asleep"))?;
+            if !(0..=60).contains(&minute) || from > minute {
+                return Err(invalid());
+            }
+            guard.sleep(from, minute);
         } else {
-            let guardId = utils::parse(&beginRex.captures(line).unwrap()["g"]);
-            let mut guard = guards.entry(guardId).or_insert(Guard::new());
+            let guardId =
+                utils::parse(&beginRex.captures(line).ok_or_else(invalid)?["g"]).line(idx)?;
+            let guard = guards.entry(guardId).or_insert_with(Guard::new);
             guard.id = guardId;
             guardOnDuty = Option::Some(guard.id);
         }
@@ -33,29 +89,7 @@ pub fn day4(lines: &mut Vec<String>) {
 
     guards.values_mut().for_each(Guard::finalize);
 
-    let sleepiestGuard = guards.values().max_by_key(|g| g.minsAsleep).unwrap();
-
-    println!(
-        "Guard # {} :: Mins Asleep = {}, Min Most Asleep = {}, Times Asleep in Min = {}, Checksum = {}",
-        sleepiestGuard.id,
-        sleepiestGuard.minsAsleep,
-        sleepiestGuard.minMost,
-        sleepiestGuard.mostInMin,
-        sleepiestGuard.id * sleepiestGuard.minMost
-    );
-
-    println!("Running Day 4 - b");
-
-    let predictableGuard = guards.values().max_by_key(|g| g.mostInMin).unwrap();
-
-    println!(
-        "Guard # {} :: Mins Asleep = {}, Min Most Asleep = {}, Times Asleep in Min = {}, Checksum = {}",
-        predictableGuard.id,
-        predictableGuard.minsAsleep,
-        predictableGuard.minMost,
-        predictableGuard.mostInMin,
-        predictableGuard.id * predictableGuard.minMost
-    );
+    Ok(guards)
 }
 
 struct Guard {
@@ -93,3 +127,39 @@ impl Guard {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    const RECORDS: [&str; 17] = [
+        "[1518-11-01 00:00] Guard #10 begins shift",
+        "[1518-11-01 00:05] falls asleep",
+        "[1518-11-01 00:25] wakes up",
+        "[1518-11-01 00:30] falls asleep",
+        "[1518-11-01 00:55] wakes up",
+        "[1518-11-01 23:58] Guard #99 begins shift",
+        "[1518-11-02 00:40] falls asleep",
+        "[1518-11-02 00:50] wakes up",
+        "[1518-11-03 00:05] Guard #10 begins shift",
+        "[1518-11-03 00:24] falls asleep",
+        "[1518-11-03 00:29] wakes up",
+        "[1518-11-04 00:02] Guard #99 begins shift",
+        "[1518-11-04 00:36] falls asleep",
+        "[1518-11-04 00:46] wakes up",
+        "[1518-11-05 00:03] Guard #99 begins shift",
+        "[1518-11-05 00:45] falls asleep",
+        "[1518-11-05 00:55] wakes up",
+    ];
+
+    #[test]
+    fn part_a_example() {
+        assert_eq!(run_example::<Day4>(Part::A, &RECORDS), 240.into());
+    }
+
+    #[test]
+    fn part_b_example() {
+        assert_eq!(run_example::<Day4>(Part::B, &RECORDS), 4455.into());
+    }
+}
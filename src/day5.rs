@@ -1,32 +1,66 @@
-pub fn day5(lines: &mut Vec<String>) {
-    println!("Running Day 5 - a");
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+use crate::utils::Input;
+
+#[derive(Default)]
+pub struct Day5 {
+    polymer: String,
+    reacted: Option<String>,
+}
+
+impl Solver for Day5 {
+    fn day(&self) -> u32 {
+        5
+    }
 
-    let mut polymer = lines.get(0).unwrap().to_owned();
-    // let mut polymer = "dabAcCaCBAcCcaDA".to_owned();
+    fn title(&self) -> &'static str {
+        "Alchemical Reduction"
+    }
 
-    // Optimization: All 'improved' polymers will still have _at least_ the same reactions
-    // as the unimproved polymer. So, assign back, and let the improved polymers not
-    // duplicate the same effort.
-    polymer = react_polymer(&polymer);
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        self.polymer = input.single_line()?.to_string();
+        if !self.polymer.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(Error::at_line(0, "polymer units should be letters"));
+        }
+        Ok(())
+    }
 
-    println!("Remaining Polymer Units = {}", polymer.len());
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        let polymer = self.reacted();
 
-    println!("Running Day 5 - b");
+        Ok(polymer.len().into())
+    }
 
-    let shortestPolymerLength = (0..26)
-        // .map(|i| (((i as u8) + 65) as char, ((i as u8) + 97) as char))
-        // .map(|t| _improve_polymer_orig(&polymer, t))
-        // .map(|p| _react_polymer_orig(&p))
-        .map(|i| ((i as u8) + 65, (i as u8) + 97))
-        .map(|u| react_improved_polymer(&polymer, u))
-        .map(|p| p.len())
-        .min()
-        .unwrap();
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        // Optimization: All 'improved' polymers will still have _at least_ the same reactions
+        // as the unimproved polymer. So, react it first, and let the improved polymers not
+        // duplicate the same effort.
+        let polymer = self.reacted();
+
+        let shortestPolymerLength = (0..26)
+            // .map(|i| (((i as u8) + 65) as char, ((i as u8) + 97) as char))
+            // .map(|t| _improve_polymer_orig(&polymer, t))
+            // .map(|p| _react_polymer_orig(&p))
+            .map(|i| ((i as u8) + 65, (i as u8) + 97))
+            .map(|u| react_improved_polymer(polymer, u))
+            .map(|p| p.len())
+            .min()
+            .unwrap();
+
+        Ok(shortestPolymerLength.into())
+    }
+}
 
-    println!("Shortest Polymer Length = {}", shortestPolymerLength);
+impl Day5 {
+    // The fully reacted polymer, shared by both parts.
+    fn reacted(&mut self) -> &str {
+        let polymer = &self.polymer;
+        self.reacted.get_or_insert_with(|| react_polymer(polymer))
+    }
 }
 
-fn _react_polymer_orig(polymer: &String) -> String {
+fn _react_polymer_orig(polymer: &str) -> String {
     let mut polymer = polymer.chars().map(|c| c as u8).collect::<Vec<u8>>();
 
     let mut idx;
@@ -35,9 +69,7 @@ fn _react_polymer_orig(polymer: &String) -> String {
         if (polymer[idx] as i32 - polymer[idx + 1] as i32).abs() == 32 {
             polymer.remove(idx);
             polymer.remove(idx);
-            if idx > 0 {
-                idx -= 1;
-            }
+            idx = idx.saturating_sub(1);
         } else {
             idx += 1;
         }
@@ -46,7 +78,7 @@ fn _react_polymer_orig(polymer: &String) -> String {
     polymer.iter().map(|c| *c as char).collect::<String>()
 }
 
-fn react_polymer(polymer: &String) -> String {
+fn react_polymer(polymer: &str) -> String {
     let mut polymer = polymer.chars().map(|c| c as u8).collect::<Vec<u8>>();
     let len = polymer.len();
 
@@ -74,7 +106,7 @@ fn react_polymer(polymer: &String) -> String {
         .collect::<String>()
 }
 
-fn react_improved_polymer(polymer: &String, remove: (u8, u8)) -> String {
+fn react_improved_polymer(polymer: &str, remove: (u8, u8)) -> String {
     let mut polymer = polymer.chars().map(|c| c as u8).collect::<Vec<u8>>();
     let len = polymer.len();
 
@@ -108,17 +140,39 @@ fn react_improved_polymer(polymer: &String, remove: (u8, u8)) -> String {
 
 #[inline]
 fn will_react(a: u8, b: u8) -> bool {
-    (a - b == 32 || b - a == 32)
+    a.wrapping_sub(b) == 32 || b.wrapping_sub(a) == 32
 }
 
 #[inline]
 fn is_removed(c: u8, unit: (u8, u8)) -> bool {
-    (c == unit.0 || c == unit.1)
+    c == unit.0 || c == unit.1
 }
 
-fn _improve_polymer_orig(polymer: &String, unitType: (char, char)) -> String {
+fn _improve_polymer_orig(polymer: &str, unitType: (char, char)) -> String {
     polymer
         .chars()
         .filter(|c| *c != unitType.0 && *c != unitType.1)
         .collect::<String>()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    #[test]
+    fn part_a_example() {
+        assert_eq!(
+            run_example::<Day5>(Part::A, &["dabAcCaCBAcCcaDA"]),
+            10.into()
+        );
+    }
+
+    #[test]
+    fn part_b_example() {
+        assert_eq!(
+            run_example::<Day5>(Part::B, &["dabAcCaCBAcCcaDA"]),
+            4.into()
+        );
+    }
+}
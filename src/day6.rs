@@ -1,102 +1,164 @@
-use crate::utils;
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+use crate::utils::{Input, Vec2};
 use itertools::Itertools;
-use pathfinding::utils::absdiff;
-use regex::Regex;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-pub fn day6(lines: &mut Vec<String>) {
-    println!("Running Day 6 - a");
+pub struct Day6 {
+    coords: Vec<Point>,
+    // A location is in the region if its total distance to every coordinate is below this.
+    max_distance: i32,
+}
 
-    let rex = Regex::new(r"(?P<x>\d+), (?P<y>\d+)").unwrap();
+impl Default for Day6 {
+    fn default() -> Self {
+        Day6 {
+            coords: Vec::new(),
+            max_distance: 10000,
+        }
+    }
+}
 
-    let coords = lines
-        .iter()
-        .map(|l| rex.captures(l).unwrap())
-        .map(|c| (utils::parse(&c[1]), utils::parse(&c[2])))
-        .collect_vec();
+impl Solver for Day6 {
+    fn day(&self) -> u32 {
+        6
+    }
 
-    let mut grid: HashMap<Point, usize> = HashMap::new();
-    let mut totals: HashMap<Point, i32> = HashMap::new();
+    fn title(&self) -> &'static str {
+        "Chronal Coordinates"
+    }
 
-    let xBounds = (
-        coords.iter().map(|c| c.0).min().unwrap(),
-        coords.iter().map(|c| c.0).max().unwrap(),
-    );
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        self.coords = input
+            .regex_rows::<(i32, i32)>(r"^(\d+), (\d+)$")?
+            .into_iter()
+            .map(Vec2::from)
+            .collect();
+        if self.coords.is_empty() {
+            return Err(Error::new("no coordinates"));
+        }
+        Ok(())
+    }
 
-    let yBounds = (
-        coords.iter().map(|c| c.1).min().unwrap(),
-        coords.iter().map(|c| c.1).max().unwrap(),
-    );
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        let coords = &self.coords;
+        let (xBounds, yBounds) = get_bounds(coords);
 
-    for x in xBounds.0..xBounds.1 + 1 {
-        for y in yBounds.0..yBounds.1 + 1 {
-            let coord = (x, y);
+        let mut grid: HashMap<Point, usize> = HashMap::new();
 
-            let manhattans = coords
-                .iter()
-                .enumerate()
-                .map(|(i, c)| ((i, *c), calcManhattan(c, &coord)))
-                .collect::<Vec<((usize, Point), i32)>>();
+        for x in xBounds.0..xBounds.1 + 1 {
+            for y in yBounds.0..yBounds.1 + 1 {
+                let coord = Vec2::new(x, y);
 
-            let total = manhattans.iter().map(|(_, i)| *i).sum();
-            totals.insert(coord, total);
+                let manhattans = coords
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| ((i, *c), c.manhattan(coord)))
+                    .collect::<Vec<((usize, Point), i32)>>();
 
-            let min = manhattans.iter().map(|i| i.1).min().unwrap();
+                let min = manhattans.iter().map(|i| i.1).min().unwrap();
 
-            let mins = manhattans
-                .into_iter()
-                .filter(|m| m.1 == min)
-                .collect::<Vec<((usize, Point), i32)>>();
+                let mins = manhattans
+                    .into_iter()
+                    .filter(|m| m.1 == min)
+                    .collect::<Vec<((usize, Point), i32)>>();
 
-            // Don't count coordinates with ties
-            if mins.len() > 1 {
-                continue;
-            }
+                // Don't count coordinates with ties
+                if mins.len() > 1 {
+                    continue;
+                }
 
-            grid.insert(coord, (mins[0].0).0);
+                grid.insert(coord, (mins[0].0).0);
+            }
         }
-    }
 
-    // Exclude coords with infinite areas.
-    let mut excludes: HashSet<usize> = HashSet::new();
-    for x in xBounds.0..xBounds.1 + 1 {
-        exclude((x, yBounds.0), &grid, &mut excludes);
-        exclude((x, yBounds.1), &grid, &mut excludes);
-    }
-    for y in yBounds.0..yBounds.1 + 1 {
-        exclude((xBounds.0, y), &grid, &mut excludes);
-        exclude((xBounds.1, y), &grid, &mut excludes);
-    }
+        // Exclude coords with infinite areas.
+        let mut excludes: HashSet<usize> = HashSet::new();
+        for x in xBounds.0..xBounds.1 + 1 {
+            exclude(Vec2::new(x, yBounds.0), &grid, &mut excludes);
+            exclude(Vec2::new(x, yBounds.1), &grid, &mut excludes);
+        }
+        for y in yBounds.0..yBounds.1 + 1 {
+            exclude(Vec2::new(xBounds.0, y), &grid, &mut excludes);
+            exclude(Vec2::new(xBounds.1, y), &grid, &mut excludes);
+        }
 
-    grid = grid
-        .into_iter()
-        .filter(|(_, i)| !excludes.contains(i))
-        .collect::<HashMap<Point, usize>>();
+        grid = grid
+            .into_iter()
+            .filter(|(_, i)| !excludes.contains(i))
+            .collect::<HashMap<Point, usize>>();
 
-    // Group grid entries by their closest coord id.
-    let counts = grid.into_iter().map(|g| (g.1, g.0)).into_group_map();
+        // Group grid entries by their closest coord id.
+        let counts = grid.into_iter().map(|g| (g.1, g.0)).into_group_map();
 
-    // Find the largest area.
-    let max = counts.values().map(|v| v.len()).max().unwrap();
+        // Find the largest area.
+        let max = counts
+            .values()
+            .map(|v| v.len())
+            .max()
+            .ok_or_else(|| Error::new("every area is infinite"))?;
 
-    println!("Largest Area = {}", max);
+        Ok(max.into())
+    }
 
-    println!("Running Day 6 - b");
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        let coords = &self.coords;
+        let (xBounds, yBounds) = get_bounds(coords);
 
-    let regions = totals.values().filter(|&t| *t < 10000).count();
+        let regions = iproduct!(xBounds.0..xBounds.1 + 1, yBounds.0..yBounds.1 + 1)
+            .map(|coord| {
+                let coord = Vec2::from(coord);
+                coords.iter().map(|c| c.manhattan(coord)).sum::<i32>()
+            })
+            .filter(|&t| t < self.max_distance)
+            .count();
 
-    println!("Region Size = {}", regions);
+        Ok(regions.into())
+    }
 }
 
-fn calcManhattan(a: &Point, b: &Point) -> i32 {
-    absdiff(a.0, b.0) + absdiff(a.1, b.1)
+fn get_bounds(coords: &[Point]) -> ((i32, i32), (i32, i32)) {
+    let xBounds = (
+        coords.iter().map(|c| c.x).min().unwrap(),
+        coords.iter().map(|c| c.x).max().unwrap(),
+    );
+
+    let yBounds = (
+        coords.iter().map(|c| c.y).min().unwrap(),
+        coords.iter().map(|c| c.y).max().unwrap(),
+    );
+
+    (xBounds, yBounds)
 }
 
 fn exclude(coord: Point, grid: &HashMap<Point, usize>, excludes: &mut HashSet<usize>) {
-    if grid.contains_key(&coord) {
-        excludes.insert(grid[&coord]);
+    if let Some(&id) = grid.get(&coord) {
+        excludes.insert(id);
     }
 }
 
-type Point = (i32, i32);
+type Point = Vec2<i32>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, run_example_with, Part};
+
+    const COORDS: [&str; 6] = ["1, 1", "1, 6", "8, 3", "3, 4", "5, 5", "8, 9"];
+
+    #[test]
+    fn part_a_example() {
+        assert_eq!(run_example::<Day6>(Part::A, &COORDS), 17.into());
+    }
+
+    #[test]
+    fn part_b_example() {
+        let day = Day6 {
+            max_distance: 32,
+            ..Day6::default()
+        };
+        assert_eq!(run_example_with(day, Part::B, &COORDS), 16.into());
+    }
+}
@@ -1,100 +1,141 @@
-use crate::utils;
-use regex::Regex;
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+use crate::utils::{FixedBitSet, Input};
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 use std::fmt;
 
-// const NUM_NODES: usize = 6;
-// const NUM_WORKERS: usize = 2;
-// const MIN_TIME: u32 = 0;
+// Steps are named by a letter, so there can be up to 26 of them.
 const NUM_NODES: usize = 26;
-const NUM_WORKERS: usize = 5;
-const MIN_TIME: u32 = 60;
+
+// A set of steps, which all fit in a single word.
+type Steps = FixedBitSet<1>;
 
 type Edge = (usize, usize);
 
-pub fn day7(lines: &mut Vec<String>) {
-    println!("Running Day 7 - a");
+pub struct Day7 {
+    edges: Vec<Edge>,
+    workers: usize,
+    // How long a step takes on top of its position in the alphabet.
+    min_time: u32,
+}
+
+impl Default for Day7 {
+    fn default() -> Self {
+        Day7 {
+            edges: Vec::new(),
+            workers: 5,
+            min_time: 60,
+        }
+    }
+}
 
-    let rex = Regex::new(r"Step (\w) .* step (\w)").unwrap();
+impl Solver for Day7 {
+    fn day(&self) -> u32 {
+        7
+    }
 
-    let edges: Vec<Edge> = lines
-        .iter()
-        .map(|l| rex.captures(l).unwrap())
-        .map(|c| (Node::get_id_from_name(&c[1]), Node::get_id_from_name(&c[2])))
-        .collect();
+    fn title(&self) -> &'static str {
+        "The Sum of Its Parts"
+    }
+
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        self.edges = parse_edges(input)?;
+        Ok(())
+    }
+
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        let mut instructions = Instructions::new(&self.edges);
 
-    let mut day7 = Day7::new(&edges);
+        let order = instructions.solve_a()?;
 
-    let order = day7.solve_a();
+        Ok(make_instruction_string(&instructions, &order).into())
+    }
 
-    println!(
-        "Instruction Order = {}",
-        make_instruction_string(&day7, &order)
-    );
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        let mut instructions = Instructions::new(&self.edges);
 
-    println!("Running Day 7 - b");
+        Ok(instructions.solve_b(self.workers, self.min_time)?.into())
+    }
+}
 
-    let order = day7.solve_b();
+fn parse_edges(input: &Input) -> Result<Vec<Edge>> {
+    let steps = input.regex_rows(r"Step ([A-Z]) .* step ([A-Z])")?;
 
-    println!("Instruction Duration = {}", order);
+    Ok(steps
+        .into_iter()
+        .map(|(a, b)| (Node::get_id_from_name(a), Node::get_id_from_name(b)))
+        .collect())
 }
 
-fn make_instruction_string(day7: &Day7, order: &[usize; NUM_NODES]) -> String {
+fn make_instruction_string(instructions: &Instructions, order: &[usize]) -> String {
     order
         .iter()
-        .map(|&i| day7.nodes[i].get_name())
+        .map(|&i| instructions.nodes[i].get_name())
         .collect::<String>()
 }
 
-struct Day7 {
+struct Instructions {
     nodes: Vec<Node>,
+    // The steps that appear in the instructions.
+    steps: Steps,
 }
 
-impl Day7 {
-    fn new(edges: &Vec<Edge>) -> Day7 {
-        let mut day7 = Day7 {
+impl Instructions {
+    fn new(edges: &[Edge]) -> Instructions {
+        let mut instructions = Instructions {
             nodes: Vec::with_capacity(NUM_NODES),
+            steps: Steps::new(),
         };
 
         for i in 0..NUM_NODES {
-            day7.nodes.push(Node::new(i));
+            instructions.nodes.push(Node::new(i));
         }
 
         for edge in edges {
-            utils::set_bit(&mut day7.nodes[edge.0].dependents, edge.1);
-            utils::set_bit(&mut day7.nodes[edge.1].dependencies, edge.0);
+            instructions.nodes[edge.0].dependents.insert(edge.1);
+            instructions.nodes[edge.1].dependencies.insert(edge.0);
+            instructions.steps.insert(edge.0);
+            instructions.steps.insert(edge.1);
         }
 
-        day7
+        instructions
     }
 
-    fn solve_a(&mut self) -> [usize; NUM_NODES] {
-        let mut visitOrder: [usize; NUM_NODES] = [0; NUM_NODES];
-        let mut visitCount = 0;
+    fn num_steps(&self) -> usize {
+        self.steps.len()
+    }
+
+    fn solve_a(&mut self) -> Result<Vec<usize>> {
+        let mut visitOrder: Vec<usize> = Vec::with_capacity(self.num_steps());
         let mut iter = self.iter();
 
         while let Some(node) = iter.next() {
             iter.visit(node);
-            visitOrder[visitCount] = node.id;
-            visitCount += 1;
+            visitOrder.push(node.id);
             // println!("Finished visiting {:?}", node);
         }
 
-        visitOrder
+        if visitOrder.len() < self.num_steps() {
+            return Err(circular_dependency());
+        }
+
+        Ok(visitOrder)
     }
 
-    fn solve_b(&mut self) -> u32 {
-        let mut workers: Vec<Worker> = Vec::with_capacity(NUM_WORKERS);
-        for _ in 0..NUM_WORKERS {
+    fn solve_b(&mut self, num_workers: usize, min_time: u32) -> Result<u32> {
+        let mut workers: Vec<Worker> = Vec::with_capacity(num_workers);
+        for _ in 0..num_workers {
             workers.push(Worker::new());
         }
+        let num_steps = self.num_steps();
 
         let mut visit_count = 0;
         let mut total_ticks = 0;
         let mut iter = self.iter();
 
-        while visit_count < NUM_NODES {
+        while visit_count < num_steps {
             // Tick all active workers down by the min remaining duration of a single worker (skip time forward)
             let mut active_workers = workers
                 .iter_mut()
@@ -140,33 +181,46 @@ impl Day7 {
                 .zip(iter.by_ref())
                 .for_each(|(w, n)| {
                     w.nodeId = Some(n.id);
-                    w.ticks = MIN_TIME + n.id as u32 + 1;
+                    w.ticks = min_time + n.id as u32 + 1;
                 });
+
+            // With nobody working and steps left over, the rest are waiting on each other.
+            if visit_count < num_steps && !workers.iter().any(Worker::active) {
+                return Err(circular_dependency());
+            }
         }
 
-        total_ticks
+        Ok(total_ticks)
     }
 
-    fn iter(&self) -> Day7Iterator<'_> {
-        Day7Iterator::new(self)
+    fn iter(&self) -> InstructionsIterator<'_> {
+        InstructionsIterator::new(self)
     }
 }
 
-struct Day7Iterator<'a> {
-    day7: &'a Day7,
-    visited: u32,
+fn circular_dependency() -> Error {
+    Error::new("the steps have a circular dependency")
+}
+
+struct InstructionsIterator<'a> {
+    instructions: &'a Instructions,
+    visited: Steps,
     queue: BinaryHeap<&'a Node>,
 }
 
-impl Day7Iterator<'_> {
-    fn new(day7: &Day7) -> Day7Iterator {
-        let mut iterator = Day7Iterator {
-            day7: day7,
-            visited: 0,
+impl InstructionsIterator<'_> {
+    fn new(instructions: &Instructions) -> InstructionsIterator<'_> {
+        let mut iterator = InstructionsIterator {
+            instructions,
+            visited: Steps::new(),
             queue: BinaryHeap::with_capacity(NUM_NODES),
         };
 
-        for node in day7.nodes.iter().filter(|n| n.dependencies == 0) {
+        let roots = instructions
+            .nodes
+            .iter()
+            .filter(|n| instructions.steps.contains(n.id) && n.dependencies.is_empty());
+        for node in roots {
             // println!("Queueing {:?}", node);
             iterator.queue.push(node);
         }
@@ -177,51 +231,44 @@ impl Day7Iterator<'_> {
     fn visit(&mut self, node: &Node) {
         // println!("Visiting {:?}", node);
 
-        utils::set_bit(&mut self.visited, node.id);
+        self.visited.insert(node.id);
 
-        for i in 0..NUM_NODES {
-            if utils::is_bit_set(&node.dependents, i) {
-                let nextNode = &self.day7.nodes[i];
-                // println!("Checking dependent {:?}...", nextNode);
-
-                if utils::intersection(nextNode.dependencies, self.visited) == nextNode.dependencies
-                {
-                    // println!("Queueing {:?}", nextNode);
-                    self.queue.push(nextNode);
-                }
+        for i in node.dependents.iter() {
+            let nextNode = &self.instructions.nodes[i];
+            // println!("Checking dependent {:?}...", nextNode);
+
+            if nextNode.dependencies.is_subset(&self.visited) {
+                // println!("Queueing {:?}", nextNode);
+                self.queue.push(nextNode);
             }
         }
     }
 }
 
-impl<'a> Iterator for Day7Iterator<'a> {
+impl<'a> Iterator for InstructionsIterator<'a> {
     type Item = &'a Node;
 
     fn next(&mut self) -> Option<&'a Node> {
-        let next = self.queue.pop();
-        // match next {
-        //     Some(node) => {
-        //         println!("Iterator :: next={:?}", node);
-        //     }
-        //     None => {}
+        // if let Some(node) = self.queue.peek() {
+        //     println!("Iterator :: next={:?}", node);
         // }
-        next
+        self.queue.pop()
     }
 }
 
 #[derive(Eq, PartialEq)]
 struct Node {
     id: usize,
-    dependencies: u32,
-    dependents: u32,
+    dependencies: Steps,
+    dependents: Steps,
 }
 
 impl Node {
     fn new(id: usize) -> Node {
         Node {
-            id: id,
-            dependencies: 0,
-            dependents: 0,
+            id,
+            dependencies: Steps::new(),
+            dependents: Steps::new(),
         }
     }
 
@@ -229,8 +276,8 @@ impl Node {
         ((self.id as u8 + 65) as char).to_string()
     }
 
-    fn get_id_from_name(name: &str) -> usize {
-        (utils::parse::<char>(name) as u8 - 65) as usize
+    fn get_id_from_name(name: char) -> usize {
+        (name as u8 - b'A') as usize
     }
 }
 
@@ -273,3 +320,34 @@ impl Worker {
         self.active() && self.ticks == 0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, run_example_with, Part};
+
+    const STEPS: [&str; 7] = [
+        "Step C must be finished before step A can begin.",
+        "Step C must be finished before step F can begin.",
+        "Step A must be finished before step B can begin.",
+        "Step A must be finished before step D can begin.",
+        "Step B must be finished before step E can begin.",
+        "Step D must be finished before step E can begin.",
+        "Step F must be finished before step E can begin.",
+    ];
+
+    #[test]
+    fn part_a_example() {
+        assert_eq!(run_example::<Day7>(Part::A, &STEPS), "CABDFE".into());
+    }
+
+    #[test]
+    fn part_b_example() {
+        let day = Day7 {
+            workers: 2,
+            min_time: 0,
+            ..Day7::default()
+        };
+        assert_eq!(run_example_with(day, Part::B, &STEPS), 15.into());
+    }
+}
@@ -1,51 +1,87 @@
-use crate::utils;
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+use crate::utils::Input;
 
-pub fn day8(lines: &mut Vec<String>) {
-    println!("Running Day 8 - a");
+#[derive(Default)]
+pub struct Day8 {
+    code: Vec<u32>,
+}
 
-    let code = lines
-        .first()
-        .unwrap()
-        .split(" ")
-        .map(|s| utils::parse(s))
-        .collect::<Vec<u32>>();
+impl Solver for Day8 {
+    fn day(&self) -> u32 {
+        8
+    }
 
-    let licenses = calc_licenses(&code);
+    fn title(&self) -> &'static str {
+        "Memory Maneuver"
+    }
 
-    println!("License = {}", licenses.0);
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        self.code = input.ints()?;
+        Ok(())
+    }
 
-    println!("Running Day 8 - b");
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(calc_licenses(&self.code)?.0.into())
+    }
 
-    println!("License = {}", licenses.1);
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        Ok(calc_licenses(&self.code)?.1.into())
+    }
 }
 
-fn calc_licenses(code: &Vec<u32>) -> (u32, u32) {
+fn calc_licenses(code: &[u32]) -> Result<(u32, u32)> {
     let mut cursor = 0;
     let mut license_a = 0;
-    let license_b = visit_node(code, &mut cursor, &mut license_a);
-    (license_a, license_b)
+    let license_b = visit_node(code, &mut cursor, &mut license_a)?;
+    Ok((license_a, license_b))
 }
 
-fn visit_node(code: &Vec<u32>, cursor: &mut usize, license_a: &mut u32) -> u32 {
-    let header = (code[*cursor] as usize, code[*cursor + 1] as usize);
-    *cursor += 2;
+fn visit_node(code: &[u32], cursor: &mut usize, license_a: &mut u32) -> Result<u32> {
+    let next = |cursor: &mut usize| -> Result<u32> {
+        let value = code
+            .get(*cursor)
+            .ok_or_else(|| Error::new("the license file ends in the middle of a node"))?;
+        *cursor += 1;
+        Ok(*value)
+    };
+
+    let header = (next(cursor)? as usize, next(cursor)? as usize);
 
     let mut child_values: Vec<u32> = Vec::with_capacity(header.0);
     for _ in 0..header.0 {
-        child_values.push(visit_node(code, cursor, license_a));
+        child_values.push(visit_node(code, cursor, license_a)?);
     }
 
     let mut value = 0;
     for _ in 0..header.1 {
-        let meta = code[*cursor];
+        let meta = next(cursor)?;
         if header.0 == 0 {
             value += meta;
         } else if 0 < meta && meta <= child_values.len() as u32 {
             value += child_values[(meta - 1) as usize];
         }
         *license_a += meta;
-        *cursor += 1;
     }
 
-    value
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    const LICENSE: [&str; 1] = ["2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2"];
+
+    #[test]
+    fn part_a_example() {
+        assert_eq!(run_example::<Day8>(Part::A, &LICENSE), 138.into());
+    }
+
+    #[test]
+    fn part_b_example() {
+        assert_eq!(run_example::<Day8>(Part::B, &LICENSE), 66.into());
+    }
 }
@@ -1,49 +1,78 @@
-use crate::utils;
-use regex::Regex;
+use crate::answer::Answer;
+use crate::cancel;
+use crate::error::{Error, LineContext, Result};
+use crate::solver::Solver;
+use crate::utils::{self, Input};
+
+#[derive(Default)]
+pub struct Day9 {
+    player_count: usize,
+    last_marble: usize,
+}
 
-pub fn day9(lines: &mut Vec<String>) {
-    println!("Running Day 9 - a");
+impl Solver for Day9 {
+    fn day(&self) -> u32 {
+        9
+    }
 
-    let rex = Regex::new(r"(\d+).* (\d+)").unwrap();
-    let caps = rex.captures(lines.get(0).unwrap()).unwrap();
-    let player_count: usize = utils::parse(&caps[1]);
-    let last_marble: usize = utils::parse(&caps[2]);
+    fn title(&self) -> &'static str {
+        "Marble Mania"
+    }
+
+    fn parse(&mut self, input: &Input) -> Result<()> {
+        let (player_count, last_marble) = parse_game(input.single_line()?)?;
+        self.player_count = player_count;
+        self.last_marble = last_marble;
+        Ok(())
+    }
 
-    let scores = solve_a(player_count, last_marble);
-    print_winner(&scores);
+    fn part_a(&mut self, _input: &Input) -> Result<Answer> {
+        let scores = solve_a(self.player_count, self.last_marble)?;
+        Ok(winning_score(&scores).into())
+    }
 
-    println!("Running Day 9 - b");
+    fn part_b(&mut self, _input: &Input) -> Result<Answer> {
+        let scores = solve_b(self.player_count, self.last_marble * 100)?;
+        Ok(winning_score(&scores).into())
+    }
+}
 
-    let scores = solve_b(player_count, last_marble * 100);
-    print_winner(&scores);
+fn parse_game(line: &str) -> Result<(usize, usize)> {
+    let (player_count, last_marble) = match utils::ints(line).line(0)?[..] {
+        [player_count, last_marble] => (player_count, last_marble),
+        _ => return Err(Error::at_line(0, format!("invalid game `{}`", line))),
+    };
+    if player_count == 0 {
+        return Err(Error::at_line(0, "the game needs at least one player"));
+    }
+    Ok((player_count, last_marble))
 }
 
-fn solve_a(player_count: usize, last_marble: usize) -> Vec<usize> {
+fn solve_a(player_count: usize, last_marble: usize) -> Result<Vec<usize>> {
     let mut cursor = 0;
     let mut cur_player = 0;
     let mut cur_marble = 1;
 
     let mut scores = vec![0; player_count];
-    let mut marbles: Vec<usize> = Vec::with_capacity((last_marble + 1) as usize);
+    let mut marbles: Vec<usize> = Vec::with_capacity(last_marble + 1);
     marbles.push(0);
 
     while cur_marble <= last_marble {
         if cur_marble % 23 == 0 {
+            cancel::check()?;
             scores[cur_player] += cur_marble;
             cursor = utils::wrap(cursor as isize - 7, marbles.len());
             scores[cur_player] += marbles.remove(cursor);
+        } else if cursor == 0 {
+            marbles.push(cur_marble);
+            cursor += 1;
         } else {
-            if cursor == 0 {
+            cursor += 2;
+            if cursor == marbles.len() {
                 marbles.push(cur_marble);
-                cursor += 1;
             } else {
-                cursor += 2;
-                if cursor == marbles.len() {
-                    marbles.push(cur_marble);
-                } else {
-                    cursor %= marbles.len();
-                    marbles.insert(cursor, cur_marble);
-                }
+                cursor %= marbles.len();
+                marbles.insert(cursor, cur_marble);
             }
         }
 
@@ -52,10 +81,10 @@ fn solve_a(player_count: usize, last_marble: usize) -> Vec<usize> {
         cur_marble += 1;
     }
 
-    scores
+    Ok(scores)
 }
 
-fn solve_b(player_count: usize, last_marble: usize) -> Vec<usize> {
+fn solve_b(player_count: usize, last_marble: usize) -> Result<Vec<usize>> {
     let mut cursor = 0;
     let mut cur_player = 0;
     let mut cur_marble = 1;
@@ -66,7 +95,7 @@ fn solve_b(player_count: usize, last_marble: usize) -> Vec<usize> {
             prev: None,
             next: None
         };
-        (last_marble + 1) as usize
+        last_marble + 1
     ];
 
     marbles.get_mut(0).unwrap().prev = Some(0);
@@ -74,6 +103,7 @@ fn solve_b(player_count: usize, last_marble: usize) -> Vec<usize> {
 
     while cur_marble <= last_marble {
         if cur_marble % 23 == 0 {
+            cancel::check()?;
             scores[cur_player] += cur_marble;
             for _ in 0..7 {
                 cursor = marbles[cursor].prev.unwrap();
@@ -91,7 +121,7 @@ fn solve_b(player_count: usize, last_marble: usize) -> Vec<usize> {
             cursor = marbles[cursor].next.unwrap();
             let next = marbles.get(cursor).unwrap().next.unwrap();
 
-            let mut inserted = marbles.get_mut(cur_marble).unwrap();
+            let inserted = marbles.get_mut(cur_marble).unwrap();
             inserted.prev = Some(cursor);
             inserted.next = Some(next);
 
@@ -105,20 +135,14 @@ fn solve_b(player_count: usize, last_marble: usize) -> Vec<usize> {
         cur_marble += 1;
     }
 
-    scores
+    Ok(scores)
 }
 
-fn print_winner(scores: &Vec<usize>) {
-    let winner = scores
-        .iter()
-        .enumerate()
-        .max_by(|a, b| a.1.cmp(&b.1))
-        .unwrap();
-
-    println!("Winning Player = {}, Score = {}", (winner.0 + 1), winner.1);
+fn winning_score(scores: &[usize]) -> usize {
+    *scores.iter().max().unwrap()
 }
 
-fn _print_marbles(marbles: &Vec<u32>, cur_player: usize, cursor: usize) {
+fn _print_marbles(marbles: &[u32], cur_player: usize, cursor: usize) {
     println!(
         "[{}] {}",
         cur_player,
@@ -139,3 +163,40 @@ struct Marble {
     prev: Option<usize>,
     next: Option<usize>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{run_example, Part};
+
+    const GAMES: [(usize, usize, usize); 6] = [
+        (9, 25, 32),
+        (10, 1618, 8317),
+        (13, 7999, 146373),
+        (17, 1104, 2764),
+        (21, 6111, 54718),
+        (30, 5807, 37305),
+    ];
+
+    #[test]
+    fn part_a_examples() {
+        for &(players, last_marble, score) in GAMES.iter() {
+            let game = format!(
+                "{} players; last marble is worth {} points",
+                players, last_marble
+            );
+            assert_eq!(run_example::<Day9>(Part::A, &[&game]), score.into());
+        }
+    }
+
+    // Part b only plays a longer game, so the same examples apply.
+    #[test]
+    fn part_b_examples() {
+        for &(players, last_marble, score) in GAMES.iter() {
+            assert_eq!(
+                winning_score(&solve_b(players, last_marble).unwrap()),
+                score
+            );
+        }
+    }
+}
@@ -1,11 +1,24 @@
-use crate::utils;
-use regex::Regex;
+use crate::cancel;
+use crate::error::{Error, Result};
+
+mod assembler;
+mod debugger;
+mod disassembler;
+
+pub use debugger::Debugger;
+pub use disassembler::{Block, Disassembly, Exit, Flow, Line};
 
 pub struct Emulator {
     pub ip: usize,
     pub registers: Registers,
 }
 
+impl Default for Emulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Emulator {
     pub fn new() -> Self {
         Emulator {
@@ -14,10 +27,17 @@ impl Emulator {
         }
     }
 
-    pub fn run_program(&mut self, program: &Program) {
+    // Runs until the ip leaves the program, which some programs never do.
+    pub fn run_program(&mut self, program: &Program) -> Result<()> {
+        let mut steps = 0usize;
         while self.ip < program.instructions.len() {
             self.run_instruction(program);
+            steps += 1;
+            if steps & 0xFFFF == 0 {
+                cancel::check()?;
+            }
         }
+        Ok(())
     }
 
     pub fn run_instruction(&mut self, program: &Program) {
@@ -35,39 +55,43 @@ pub struct Program {
 }
 
 impl Program {
-    pub fn new(lines: &Vec<String>) -> Self {
-        let rex = Regex::new(r"(\w{4}) (\d+) (\d+) (\d+)").unwrap();
-
-        Program {
-            ip_register: utils::parse(lines[0].split(' ').last().unwrap()),
-            instructions: lines
-                .iter()
-                .skip(1)
-                .map(|l| Program::parse_instruction(l, &rex))
-                .collect(),
-        }
+    // Assembles the lines of a puzzle input, or a hand-written program that uses labels and names.
+    pub fn new(lines: &[String]) -> Result<Self> {
+        assembler::assemble(lines)
     }
+}
 
-    fn parse_instruction(line: &String, rex: &Regex) -> Instruction {
-        let c = rex.captures(line).unwrap();
-        Instruction {
-            op: Op::from_string(&c[1]),
-            data: InstructionData {
-                a: utils::parse(&c[2]),
-                b: utils::parse(&c[3]),
-                c: utils::parse(&c[4]),
-            },
-        }
-    }
+// Assembles a program that a test writes out a line at a time.
+#[cfg(test)]
+fn example_program(lines: &[&str]) -> Result<Program> {
+    Program::new(&lines.iter().map(|l| l.to_string()).collect::<Vec<_>>())
 }
 
-pub type Registers = [usize; 6];
+pub const REGISTER_COUNT: usize = 6;
+
+pub type Registers = [usize; REGISTER_COUNT];
 
 pub struct Instruction {
     pub op: Op,
     pub data: InstructionData,
 }
 
+impl Instruction {
+    // Checks that every register the instruction uses exists.
+    pub fn new(op: Op, data: InstructionData) -> Result<Self> {
+        let (a_register, b_register) = op.register_operands();
+        let registers = [(a_register, data.a), (b_register, data.b), (true, data.c)];
+
+        match registers
+            .iter()
+            .find(|&&(is_register, r)| is_register && r >= REGISTER_COUNT)
+        {
+            Some((_, r)) => Err(Error::new(format!("`{}` uses missing register {}", op, r))),
+            None => Ok(Instruction { op, data }),
+        }
+    }
+}
+
 pub struct InstructionData {
     pub a: usize,
     pub b: usize,
@@ -106,8 +130,8 @@ pub enum Op {
 }
 
 impl Op {
-    fn from_string(s: &str) -> Op {
-        match s {
+    pub fn from_string(s: &str) -> Result<Op> {
+        Ok(match s {
             "addr" => Op::addr,
             "addi" => Op::addi,
             "mulr" => Op::mulr,
@@ -124,11 +148,23 @@ impl Op {
             "eqir" => Op::eqir,
             "eqri" => Op::eqri,
             "eqrr" => Op::eqrr,
-            _ => panic!(),
+            _ => return Err(Error::new(format!("unknown op `{}`", s))),
+        })
+    }
+
+    // Whether operands a and b name registers, rather than being immediate values.
+    fn register_operands(self) -> (bool, bool) {
+        match self {
+            Op::addr | Op::mulr | Op::banr | Op::borr | Op::gtrr | Op::eqrr => (true, true),
+            Op::addi | Op::muli | Op::bani | Op::bori | Op::gtri | Op::eqri | Op::setr => {
+                (true, false)
+            }
+            Op::seti => (false, false),
+            Op::gtir | Op::eqir => (false, true),
         }
     }
 
-    fn to_string(&self) -> &str {
+    fn name(self) -> &'static str {
         match self {
             Op::addr => "addr",
             Op::addi => "addi",
@@ -168,7 +204,7 @@ impl Op {
             Op::eqri,
             Op::eqrr,
         ];
-        OPS.into_iter()
+        OPS.iter()
     }
 
     pub fn op(&self, r: &mut Registers, i: &InstructionData) {
@@ -231,6 +267,6 @@ impl Op {
 
 impl std::fmt::Display for Op {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+        write!(f, "{}", self.name())
     }
 }
@@ -0,0 +1,264 @@
+use crate::emulator::{Instruction, InstructionData, Op, Program, REGISTER_COUNT};
+use crate::error::{Error, LineContext, Result};
+use crate::utils;
+use std::collections::HashMap;
+
+// Assembles the puzzle's format, with a few conveniences on top for hand-written programs:
+//
+//     #reg ip r1              ; names a register, on top of r0 to r5
+//     #const target 10551236  ; names a value
+//     #ip ip
+//     start:                  ; names the index of the next instruction
+//         seti target 0 r4
+//         addi r4 1 r4
+//         jmp start           ; the same as `seti start-1 0 ip`
+//
+// Comments start with `;`, and values can have a number added or taken away, like `start+2`.
+// Plain numbers work everywhere, so puzzle inputs assemble as they are.
+pub fn assemble(lines: &[String]) -> Result<Program> {
+    let mut symbols = Symbols::default();
+    let mut ip_register = None;
+    let mut statements = Vec::new();
+
+    // Collect every name first, so labels can be jumped to before they're defined.
+    for (idx, line) in lines.iter().enumerate() {
+        let mut line = line.split(';').next().unwrap().trim();
+
+        if let Some(directive) = line.strip_prefix('#') {
+            let words = directive.split_whitespace().collect::<Vec<_>>();
+            match words[..] {
+                ["ip", register] if ip_register.is_none() => ip_register = Some((idx, register)),
+                ["ip", _] => return Err(Error::at_line(idx, "the `#ip` is already declared")),
+                ["reg", name, register] => symbols.define(idx, name, Symbol::Register(register))?,
+                ["const", name, value] => {
+                    let value = utils::parse(value).line(idx)?;
+                    symbols.define(idx, name, Symbol::Value(value))?;
+                }
+                _ => return Err(Error::at_line(idx, format!("invalid directive `{}`", line))),
+            }
+            continue;
+        }
+
+        if let Some(colon) = line.find(':') {
+            symbols.define(idx, line[..colon].trim(), Symbol::Value(statements.len()))?;
+            line = line[colon + 1..].trim();
+        }
+        if !line.is_empty() {
+            statements.push((idx, line));
+        }
+    }
+
+    let ip_register = match ip_register {
+        Some((idx, register)) => symbols.register(register).line(idx)?,
+        None => return Err(Error::new("expected an `#ip` declaration")),
+    };
+
+    let instructions = statements
+        .into_iter()
+        .map(|(idx, line)| symbols.instruction(line, ip_register).line(idx))
+        .collect::<Result<_>>()?;
+
+    Ok(Program {
+        ip_register,
+        instructions,
+    })
+}
+
+enum Symbol<'a> {
+    // A register, as it was written.
+    Register(&'a str),
+    Value(usize),
+}
+
+#[derive(Default)]
+struct Symbols<'a> {
+    symbols: HashMap<&'a str, Symbol<'a>>,
+}
+
+impl<'a> Symbols<'a> {
+    fn define(&mut self, idx: usize, name: &'a str, symbol: Symbol<'a>) -> Result<()> {
+        let valid = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(Error::at_line(idx, format!("invalid name `{}`", name)));
+        }
+        if is_register_name(name) || name == "jmp" || Op::from_string(name).is_ok() {
+            return Err(Error::at_line(idx, format!("`{}` is reserved", name)));
+        }
+        if self.symbols.insert(name, symbol).is_some() {
+            return Err(Error::at_line(
+                idx,
+                format!("`{}` is already defined", name),
+            ));
+        }
+        Ok(())
+    }
+
+    fn instruction(&self, line: &str, ip_register: usize) -> Result<Instruction> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        match words[..] {
+            ["jmp", target] => {
+                // The ip moves on after every instruction, so land just before the target.
+                let a = self.value(target)?.checked_sub(1).ok_or_else(|| {
+                    Error::new("can't jump to the first instruction, as the ip moves on after it")
+                })?;
+                Instruction::new(
+                    Op::seti,
+                    InstructionData {
+                        a,
+                        b: 0,
+                        c: ip_register,
+                    },
+                )
+            }
+            [op, a, b, c] => {
+                let op = Op::from_string(op)?;
+                let (a_register, b_register) = op.register_operands();
+                Instruction::new(
+                    op,
+                    InstructionData {
+                        a: self.operand(a, a_register)?,
+                        b: self.operand(b, b_register)?,
+                        c: self.register(c)?,
+                    },
+                )
+            }
+            _ => Err(Error::new(format!("invalid instruction `{}`", line))),
+        }
+    }
+
+    fn operand(&self, operand: &str, is_register: bool) -> Result<usize> {
+        if is_register {
+            self.register(operand)
+        } else {
+            self.value(operand)
+        }
+    }
+
+    // A register number, `r0` to `r5`, or a name for one of those.
+    fn register(&self, register: &str) -> Result<usize> {
+        match self.symbols.get(register) {
+            Some(Symbol::Register(aliased)) => plain_register(aliased),
+            Some(Symbol::Value(_)) => Err(Error::new(format!(
+                "`{}` is a value, not a register",
+                register
+            ))),
+            None => plain_register(register),
+        }
+    }
+
+    // A number or a named value, plus or minus a number.
+    fn value(&self, value: &str) -> Result<usize> {
+        let (base, offset) = match value.rfind(['+', '-']) {
+            Some(i) if i > 0 => (&value[..i], Some((&value[i..i + 1], &value[i + 1..]))),
+            _ => (value, None),
+        };
+
+        let base = match self.symbols.get(base) {
+            Some(&Symbol::Value(v)) => v,
+            Some(Symbol::Register(_)) => {
+                return Err(Error::new(format!("`{}` is a register, not a value", base)))
+            }
+            None => base
+                .parse()
+                .map_err(|_| Error::new(format!("unknown value `{}`", base)))?,
+        };
+
+        match offset {
+            Some(("+", n)) => Ok(base + utils::parse::<usize>(n)?),
+            Some((_, n)) => base
+                .checked_sub(utils::parse(n)?)
+                .ok_or_else(|| Error::new(format!("`{}` is below zero", value))),
+            None => Ok(base),
+        }
+    }
+}
+
+fn plain_register(register: &str) -> Result<usize> {
+    let number = if is_register_name(register) {
+        &register[1..]
+    } else {
+        register
+    };
+    match number.parse() {
+        Ok(r) if r < REGISTER_COUNT => Ok(r),
+        Ok(_) => Err(Error::new(format!("no register {}", number))),
+        Err(_) => Err(Error::new(format!("unknown register `{}`", register))),
+    }
+}
+
+fn is_register_name(name: &str) -> bool {
+    name.len() > 1 && name.starts_with('r') && name[1..].chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator::{example_program, Emulator};
+
+    fn listing(program: &Program) -> Vec<String> {
+        program.instructions.iter().map(|i| i.to_string()).collect()
+    }
+
+    #[test]
+    fn symbols_lower_to_plain_instructions() {
+        let program = example_program(&[
+            "; Counts r1 up to `limit`.",
+            "#reg ip r5",
+            "#reg count r1",
+            "#const limit 10",
+            "#ip ip",
+            "",
+            "    seti 0 0 count",
+            "loop:",
+            "    addi count 1 count",
+            "    gtri count limit-1 r0  ; done yet?",
+            "    addr ip r0 ip",
+            "    jmp loop",
+            "end: seti end 0 r2",
+        ])
+        .unwrap();
+
+        assert_eq!(program.ip_register, 5);
+        assert_eq!(
+            listing(&program),
+            [
+                "seti 0 0 1",
+                "addi 1 1 1",
+                "gtri 1 9 0",
+                "addr 5 0 5",
+                "seti 0 0 5",
+                "seti 5 0 2",
+            ]
+        );
+
+        let mut emulator = Emulator::new();
+        emulator.run_program(&program).unwrap();
+        assert_eq!(emulator.registers[..3], [1, 10, 5]);
+    }
+
+    #[test]
+    fn errors_point_at_the_line() {
+        let error = |program: &[&str]| {
+            let e = example_program(program).err().unwrap();
+            (e.line, e.message)
+        };
+
+        assert_eq!(
+            error(&["#ip 0", "seti 1 0 r6"]),
+            (Some(2), "no register 6".to_string())
+        );
+        assert_eq!(
+            error(&["#ip 0", "#reg x r1", "addi 1 x 1"]),
+            (Some(3), "`x` is a register, not a value".to_string())
+        );
+        assert_eq!(
+            error(&["#ip 0", "a: seti 0 0 1", "a: seti 0 0 1"]),
+            (Some(3), "`a` is already defined".to_string())
+        );
+        assert_eq!(
+            error(&["seti 1 0 0"]),
+            (None, "expected an `#ip` declaration".to_string())
+        );
+    }
+}
@@ -0,0 +1,378 @@
+use crate::emulator::{Emulator, Program, REGISTER_COUNT};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "\
+Commands:
+  s, step [N]                 Run N instructions (default: 1)
+  c, continue                 Run until a breakpoint, a watch or the end
+  b, break IP [if rN OP V]    Stop before running IP, if rN OP V holds
+  b, break if rN OP V         Stop whenever rN OP V holds, e.g. `break if r0 > 5`
+  d, delete N                 Delete breakpoint N
+  w, watch rN                 Stop whenever rN changes
+  unwatch rN                  Stop watching rN
+  set rN V, set ip V          Change a register or the ip
+  r, registers                Print the ip and registers
+  i, info                     List the breakpoints and watches
+  h, help                     Print this message
+  q, quit                     Stop debugging";
+
+// Steps through a program a command at a time, like a tiny gdb.
+pub struct Debugger<'a> {
+    program: &'a Program,
+    emulator: Emulator,
+    // Numbered from 1, in the order they were added.
+    breakpoints: Vec<(usize, Breakpoint)>,
+    next_breakpoint: usize,
+    watches: Vec<usize>,
+    steps: usize,
+}
+
+struct Breakpoint {
+    ip: Option<usize>,
+    condition: Option<Condition>,
+}
+
+struct Condition {
+    register: usize,
+    op: &'static str,
+    value: usize,
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(program: &'a Program, emulator: Emulator) -> Self {
+        Debugger {
+            program,
+            emulator,
+            breakpoints: Vec::new(),
+            next_breakpoint: 1,
+            watches: Vec::new(),
+            steps: 0,
+        }
+    }
+
+    pub fn emulator(&self) -> &Emulator {
+        &self.emulator
+    }
+
+    // Reads commands until `quit` or the end of the input.
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, mut output: W) -> io::Result<()> {
+        self.show_instruction(&mut output)?;
+        let mut lines = input.lines();
+        loop {
+            write!(output, "(emu) ")?;
+            output.flush()?;
+            let line = match lines.next() {
+                Some(line) => line?,
+                None => return Ok(()),
+            };
+            if !self.execute(line.trim(), &mut output)? {
+                return Ok(());
+            }
+        }
+    }
+
+    // Runs a single command. Returns false when it's time to quit.
+    pub fn execute<W: Write>(&mut self, command: &str, output: &mut W) -> io::Result<bool> {
+        let words = command.split_whitespace().collect::<Vec<_>>();
+        let result = match words[..] {
+            [] => Ok(()),
+            ["q"] | ["quit"] => return Ok(false),
+            ["h"] | ["help"] => writeln!(output, "{}", HELP).map_err(Into::into),
+            ["s"] | ["step"] => self.resume(Some(1), output),
+            ["s", n] | ["step", n] => match n.parse() {
+                Ok(n) if n > 0 => self.resume(Some(n), output),
+                _ => Err(format!("invalid number of steps `{}`", n).into()),
+            },
+            ["c"] | ["continue"] => self.resume(None, output),
+            ["b", ..] | ["break", ..] => self.add_breakpoint(&words[1..], output),
+            ["d", n] | ["delete", n] => self.delete_breakpoint(n),
+            ["w", r] | ["watch", r] => parse_register(r).map(|r| {
+                if !self.watches.contains(&r) {
+                    self.watches.push(r);
+                }
+            }),
+            ["unwatch", r] => parse_register(r).map(|r| self.watches.retain(|&w| w != r)),
+            ["set", "ip", v] => parse_value(v).map(|v| self.emulator.ip = v),
+            ["set", r, v] => parse_register(r)
+                .and_then(|r| Ok((r, parse_value(v)?)))
+                .map(|(r, v)| self.emulator.registers[r] = v),
+            ["r"] | ["registers"] => self.show_registers(output).map_err(Into::into),
+            ["i"] | ["info"] => self.show_info(output).map_err(Into::into),
+            _ => Err(format!("unknown command `{}`, try `help`", command).into()),
+        };
+
+        match result {
+            Ok(()) => Ok(true),
+            Err(Failure::Io(e)) => Err(e),
+            Err(Failure::Command(message)) => {
+                writeln!(output, "error: {}", message)?;
+                Ok(true)
+            }
+        }
+    }
+
+    fn halted(&self) -> bool {
+        self.emulator.ip >= self.program.instructions.len()
+    }
+
+    // Runs until a breakpoint, a watched register changes or the program ends,
+    // or for at most `limit` instructions.
+    fn resume<W: Write>(&mut self, limit: Option<usize>, output: &mut W) -> Outcome {
+        let mut steps = 0;
+        while limit != Some(steps) {
+            if self.halted() {
+                break;
+            }
+
+            let before = self.emulator.registers;
+            self.emulator.run_instruction(self.program);
+            self.steps += 1;
+            steps += 1;
+
+            let mut stop = false;
+            for &r in &self.watches {
+                let (old, new) = (before[r], self.emulator.registers[r]);
+                if old != new {
+                    writeln!(output, "r{}: {} -> {}", r, old, new)?;
+                    stop = true;
+                }
+            }
+            if let Some((n, breakpoint)) = self.breakpoints.iter().find(|(_, b)| self.hits(b)) {
+                writeln!(output, "breakpoint {}: {}", n, breakpoint)?;
+                stop = true;
+            }
+            if stop {
+                break;
+            }
+        }
+
+        self.show_instruction(output).map_err(Into::into)
+    }
+
+    fn hits(&self, breakpoint: &Breakpoint) -> bool {
+        breakpoint.ip.is_none_or(|ip| ip == self.emulator.ip)
+            && breakpoint.condition.as_ref().is_none_or(|c| {
+                let r = self.emulator.registers[c.register];
+                match c.op {
+                    "==" => r == c.value,
+                    "!=" => r != c.value,
+                    "<" => r < c.value,
+                    "<=" => r <= c.value,
+                    ">" => r > c.value,
+                    _ => r >= c.value,
+                }
+            })
+    }
+
+    fn add_breakpoint<W: Write>(&mut self, args: &[&str], output: &mut W) -> Outcome {
+        let (ip, condition) = match args {
+            [ip] => (Some(parse_value(ip)?), None),
+            [ip, "if", condition @ ..] => (Some(parse_value(ip)?), Some(condition)),
+            ["if", condition @ ..] => (None, Some(condition)),
+            _ => {
+                return Err(
+                    "expected `break IP`, `break IP if rN OP V` or `break if rN OP V`".into(),
+                )
+            }
+        };
+        let condition = match condition {
+            Some(&[register, op, value]) => Some(Condition {
+                register: parse_register(register)?,
+                op: ["==", "!=", "<", "<=", ">", ">="]
+                    .iter()
+                    .find(|&&o| o == op)
+                    .copied()
+                    .ok_or_else(|| format!("unknown comparison `{}`", op))?,
+                value: parse_value(value)?,
+            }),
+            Some(_) => return Err("expected a condition like `r0 > 5`".into()),
+            None => None,
+        };
+
+        let breakpoint = Breakpoint { ip, condition };
+        writeln!(
+            output,
+            "breakpoint {}: {}",
+            self.next_breakpoint, breakpoint
+        )?;
+        self.breakpoints.push((self.next_breakpoint, breakpoint));
+        self.next_breakpoint += 1;
+        Ok(())
+    }
+
+    fn delete_breakpoint(&mut self, n: &str) -> Outcome {
+        let n = parse_value(n)?;
+        match self.breakpoints.iter().position(|&(i, _)| i == n) {
+            Some(i) => {
+                self.breakpoints.remove(i);
+                Ok(())
+            }
+            None => Err(format!("no breakpoint {}", n).into()),
+        }
+    }
+
+    // The instruction that runs next, using the emulator's own format.
+    fn show_instruction<W: Write>(&self, output: &mut W) -> io::Result<()> {
+        match self.program.instructions.get(self.emulator.ip) {
+            Some(instruction) => writeln!(output, "{:>4}: {}", self.emulator.ip, instruction),
+            None => writeln!(output, "halted after {} steps", self.steps),
+        }
+    }
+
+    fn show_registers<W: Write>(&self, output: &mut W) -> io::Result<()> {
+        let registers = self
+            .emulator
+            .registers
+            .iter()
+            .enumerate()
+            .map(|(r, v)| format!("r{}={}", r, v))
+            .collect::<Vec<_>>();
+        writeln!(output, "ip={} {}", self.emulator.ip, registers.join(" "))
+    }
+
+    fn show_info<W: Write>(&self, output: &mut W) -> io::Result<()> {
+        for (n, breakpoint) in &self.breakpoints {
+            writeln!(output, "breakpoint {}: {}", n, breakpoint)?;
+        }
+        for r in &self.watches {
+            writeln!(output, "watch r{}", r)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.ip, &self.condition) {
+            (Some(ip), Some(c)) => write!(f, "ip {} if {}", ip, c),
+            (Some(ip), None) => write!(f, "ip {}", ip),
+            (None, Some(c)) => write!(f, "if {}", c),
+            (None, None) => write!(f, "always"),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "r{} {} {}", self.register, self.op, self.value)
+    }
+}
+
+// A command either can't write its output, or is one the debugger can't do.
+enum Failure {
+    Io(io::Error),
+    Command(String),
+}
+
+type Outcome = std::result::Result<(), Failure>;
+
+impl From<io::Error> for Failure {
+    fn from(e: io::Error) -> Self {
+        Failure::Io(e)
+    }
+}
+
+impl From<String> for Failure {
+    fn from(message: String) -> Self {
+        Failure::Command(message)
+    }
+}
+
+impl From<&str> for Failure {
+    fn from(message: &str) -> Self {
+        Failure::Command(message.to_string())
+    }
+}
+
+fn parse_register(s: &str) -> std::result::Result<usize, Failure> {
+    match s.strip_prefix('r').and_then(|r| r.parse().ok()) {
+        Some(r) if r < REGISTER_COUNT => Ok(r),
+        _ => Err(format!(
+            "invalid register `{}`, expected r0 to r{}",
+            s,
+            REGISTER_COUNT - 1
+        )
+        .into()),
+    }
+}
+
+fn parse_value(s: &str) -> std::result::Result<usize, Failure> {
+    s.parse()
+        .map_err(|_| format!("invalid number `{}`", s).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator::example_program;
+
+    // Counts r1 up to 10, sets r2 to 7 and halts.
+    const PROGRAM: [&str; 7] = [
+        "#ip 5",
+        "seti 0 0 1",
+        "addi 1 1 1",
+        "gtri 1 9 0",
+        "addr 0 5 5",
+        "seti 0 0 5",
+        "seti 7 0 2",
+    ];
+
+    fn debug(commands: &[&str]) -> (String, Emulator) {
+        let program = example_program(&PROGRAM).unwrap();
+        let mut debugger = Debugger::new(&program, Emulator::new());
+
+        let mut output = Vec::new();
+        debugger
+            .run(commands.join("\n").as_bytes(), &mut output)
+            .unwrap();
+
+        let emulator = debugger.emulator();
+        let emulator = Emulator {
+            ip: emulator.ip,
+            registers: emulator.registers,
+        };
+        (String::from_utf8(output).unwrap(), emulator)
+    }
+
+    #[test]
+    fn steps_and_breakpoints() {
+        let (output, emulator) = debug(&["step 2", "break 4", "continue", "r"]);
+
+        assert!(output.starts_with("   0: seti 0 0 1\n(emu)    2: gtri 1 9 0\n"));
+        assert!(output.contains("breakpoint 1: ip 4\n   4: seti 0 0 5\n"));
+        assert!(output.contains("ip=4 r0=0 r1=1 r2=0 r3=0 r4=0 r5=3\n"));
+        assert_eq!(emulator.registers[1], 1);
+    }
+
+    #[test]
+    fn conditions_watches_and_set() {
+        let (output, emulator) = debug(&[
+            "break if r1 == 3",
+            "c",
+            "delete 1",
+            "watch r2",
+            "set r1 8",
+            "c",
+            "c",
+            "step",
+        ]);
+
+        assert!(output.contains("breakpoint 1: if r1 == 3\n   2: gtri 1 9 0\n"));
+        assert!(output.contains("r2: 0 -> 7\nhalted after 21 steps\n"));
+        assert!(output.ends_with("halted after 21 steps\n(emu) "));
+        assert_eq!(emulator.registers[1], 10);
+        assert_eq!(emulator.registers[2], 7);
+    }
+
+    #[test]
+    fn bad_commands_are_reported() {
+        let (output, _) = debug(&["set r6 1", "break 3 if r0 ~ 1", "jump", "q", "step"]);
+
+        assert!(output.contains("error: invalid register `r6`, expected r0 to r5\n"));
+        assert!(output.contains("error: unknown comparison `~`\n"));
+        assert!(output.contains("error: unknown command `jump`, try `help`\n"));
+        // Nothing runs after quitting.
+        assert!(!output.contains("   1: "));
+    }
+}
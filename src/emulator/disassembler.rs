@@ -0,0 +1,436 @@
+use crate::emulator::{Instruction, Op, Program};
+use std::fmt;
+
+// A program as pseudo-code, split into basic blocks.
+pub struct Disassembly {
+    pub lines: Vec<Line>,
+    pub blocks: Vec<Block>,
+}
+
+pub struct Line {
+    pub index: usize,
+    // Reads like `r3 = r1 * r5` or `if r2 > r4 goto 12`.
+    pub code: String,
+    pub flow: Flow,
+}
+
+// Where the program goes after an instruction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Flow {
+    Next,
+    // Always jumps, or halts if it's past the end.
+    Goto(usize),
+    // Jumps if a condition holds, or else carries on.
+    Branch(usize),
+    // Jumps somewhere that depends on the registers.
+    Computed,
+}
+
+// Instructions that always run one after another, from `start` up to `end`.
+pub struct Block {
+    pub start: usize,
+    pub end: usize,
+    pub exits: Vec<Exit>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Exit {
+    // The index of a block.
+    Block(usize),
+    Halt,
+    // A computed jump, that could go anywhere.
+    Unknown,
+}
+
+impl Program {
+    // Writes to the ip register are jumps, and the ip register reads as the current index.
+    // A comparison followed by adding its result to the ip is a branch over the next instruction.
+    pub fn disassemble(&self) -> Disassembly {
+        let lines = self
+            .instructions
+            .iter()
+            .enumerate()
+            .map(|(index, instruction)| self.disassemble_line(index, instruction))
+            .collect::<Vec<_>>();
+
+        let len = lines.len();
+        let mut leaders = vec![0];
+        for line in &lines {
+            match line.flow {
+                Flow::Next => continue,
+                Flow::Goto(target) | Flow::Branch(target) => leaders.push(target),
+                Flow::Computed => {}
+            }
+            leaders.push(line.index + 1);
+        }
+        leaders.retain(|&l| l < len);
+        leaders.sort_unstable();
+        leaders.dedup();
+
+        let block_at = |index: usize| match leaders.binary_search(&index) {
+            Ok(block) => Exit::Block(block),
+            Err(_) => Exit::Halt,
+        };
+
+        let blocks = leaders
+            .iter()
+            .enumerate()
+            .map(|(i, &start)| {
+                let end = leaders.get(i + 1).copied().unwrap_or(len);
+                let exits = match lines[end - 1].flow {
+                    Flow::Next => vec![block_at(end)],
+                    Flow::Goto(target) => vec![block_at(target)],
+                    Flow::Branch(target) => vec![block_at(end), block_at(target)],
+                    Flow::Computed => vec![Exit::Unknown],
+                };
+                Block { start, end, exits }
+            })
+            .collect();
+
+        Disassembly { lines, blocks }
+    }
+
+    fn disassemble_line(&self, index: usize, instruction: &Instruction) -> Line {
+        let operand = |r| self.operand(index, r);
+        let (op, d) = (instruction.op, &instruction.data);
+        let (a_register, b_register) = op.register_operands();
+        let a = if a_register {
+            operand(d.a)
+        } else {
+            Operand::Value(d.a)
+        };
+        let b = if b_register {
+            operand(d.b)
+        } else {
+            Operand::Value(d.b)
+        };
+        let expr = Expr::new(op, a, b);
+
+        if d.c != self.ip_register {
+            let code = format!("r{} = {}", d.c, expr);
+            return Line {
+                index,
+                code,
+                flow: Flow::Next,
+            };
+        }
+
+        // The ip moves on after every instruction, so the jump lands one past what's written.
+        let (code, flow) = match expr {
+            Expr::Operand(Operand::Value(v)) => {
+                (goto(v + 1, self.instructions.len()), Flow::Goto(v + 1))
+            }
+            Expr::Binary(Operand::Value(v), "+", Operand::Register(r))
+            | Expr::Binary(Operand::Register(r), "+", Operand::Value(v)) => {
+                // Only adding to the ip itself skips the next instruction, or else carries on.
+                // Any other value would need two targets, neither of them the next instruction.
+                match self.condition(index, r).filter(|_| v == index) {
+                    Some(condition) => (
+                        format!("if {} goto {}", condition, v + 2),
+                        Flow::Branch(v + 2),
+                    ),
+                    None => (format!("goto r{} + {}", r, v + 1), Flow::Computed),
+                }
+            }
+            expr => (format!("goto {} + 1", expr), Flow::Computed),
+        };
+        Line { index, code, flow }
+    }
+
+    // The ip register reads as the index of the instruction that's running.
+    fn operand(&self, index: usize, register: usize) -> Operand {
+        if register == self.ip_register {
+            Operand::Value(index)
+        } else {
+            Operand::Register(register)
+        }
+    }
+
+    // The comparison right before `index`, if that's what set the register.
+    fn condition(&self, index: usize, register: usize) -> Option<Expr> {
+        let previous = &self.instructions[index.checked_sub(1)?];
+        let (op, d) = (previous.op, &previous.data);
+        if d.c != register || compare_symbol(op).is_none() {
+            return None;
+        }
+        let (a_register, b_register) = op.register_operands();
+        // A comparison that overwrites one of its own operands can't be repeated, so test its result.
+        if (a_register && d.a == d.c) || (b_register && d.b == d.c) {
+            return Some(Expr::Operand(Operand::Register(register)));
+        }
+        let operand = |is_register, v| {
+            if is_register {
+                self.operand(index - 1, v)
+            } else {
+                Operand::Value(v)
+            }
+        };
+        Some(Expr::new(
+            op,
+            operand(a_register, d.a),
+            operand(b_register, d.b),
+        ))
+    }
+}
+
+fn goto(target: usize, len: usize) -> String {
+    if target < len {
+        format!("goto {}", target)
+    } else {
+        format!("goto {} (halt)", target)
+    }
+}
+
+fn compare_symbol(op: Op) -> Option<&'static str> {
+    match op {
+        Op::gtir | Op::gtri | Op::gtrr => Some(">"),
+        Op::eqir | Op::eqri | Op::eqrr => Some("=="),
+        _ => None,
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Operand {
+    Register(usize),
+    Value(usize),
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::Register(r) => write!(f, "r{}", r),
+            Operand::Value(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+enum Expr {
+    Operand(Operand),
+    Binary(Operand, &'static str, Operand),
+}
+
+impl Expr {
+    // Works out the value when both operands are known.
+    fn new(op: Op, a: Operand, b: Operand) -> Self {
+        let symbol = match op {
+            Op::setr | Op::seti => return Expr::Operand(a),
+            Op::addr | Op::addi => "+",
+            Op::mulr | Op::muli => "*",
+            Op::banr | Op::bani => "&",
+            Op::borr | Op::bori => "|",
+            _ => compare_symbol(op).unwrap(),
+        };
+        match (a, b) {
+            (Operand::Value(a), Operand::Value(b)) => Expr::Operand(Operand::Value(match symbol {
+                "+" => a.wrapping_add(b),
+                "*" => a.wrapping_mul(b),
+                "&" => a & b,
+                "|" => a | b,
+                ">" => (a > b) as usize,
+                _ => (a == b) as usize,
+            })),
+            _ => Expr::Binary(a, symbol, b),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Operand(a) => write!(f, "{}", a),
+            Expr::Binary(a, symbol, b) => write!(f, "{} {} {}", a, symbol, b),
+        }
+    }
+}
+
+impl Disassembly {
+    // The control-flow graph in Graphviz's DOT language, a node per block.
+    pub fn to_dot(&self) -> String {
+        let mut dot =
+            String::from("digraph program {\n    node [shape=box, fontname=monospace];\n");
+
+        for (i, block) in self.blocks.iter().enumerate() {
+            let code = self.lines[block.start..block.end]
+                .iter()
+                .map(|l| format!("{}: {}\\l", l.index, l.code))
+                .collect::<String>();
+            dot += &format!("    b{} [label=\"{}\"];\n", i, code);
+        }
+
+        let mut halts = false;
+        let mut unknown = false;
+        for (i, block) in self.blocks.iter().enumerate() {
+            for exit in &block.exits {
+                match exit {
+                    Exit::Block(to) => dot += &format!("    b{} -> b{};\n", i, to),
+                    Exit::Halt => {
+                        halts = true;
+                        dot += &format!("    b{} -> halt;\n", i);
+                    }
+                    Exit::Unknown => {
+                        unknown = true;
+                        dot += &format!("    b{} -> unknown [style=dashed];\n", i);
+                    }
+                }
+            }
+        }
+        if halts {
+            dot += "    halt [shape=oval];\n";
+        }
+        if unknown {
+            dot += "    unknown [shape=oval, label=\"?\"];\n";
+        }
+
+        dot + "}\n"
+    }
+}
+
+// A listing with a heading for each block, and where it goes next.
+impl fmt::Display for Disassembly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, block) in self.blocks.iter().enumerate() {
+            writeln!(f, "b{}:", i)?;
+            for line in &self.lines[block.start..block.end] {
+                writeln!(f, "{:>6}: {}", line.index, line.code)?;
+            }
+            let exits = block
+                .exits
+                .iter()
+                .map(|exit| match exit {
+                    Exit::Block(to) => format!("b{}", to),
+                    Exit::Halt => "halt".to_string(),
+                    Exit::Unknown => "?".to_string(),
+                })
+                .collect::<Vec<_>>();
+            writeln!(f, "        -> {}", exits.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator::example_program;
+
+    fn code(disassembly: &Disassembly) -> Vec<&str> {
+        disassembly.lines.iter().map(|l| l.code.as_str()).collect()
+    }
+
+    #[test]
+    fn branches_and_loops() {
+        let disassembly = example_program(&[
+            "#ip 5",
+            "seti 0 0 0",
+            "addi 0 1 0",
+            "gtri 0 9 1",
+            "addr 1 5 5",
+            "seti 0 0 5",
+            "mulr 5 5 5",
+        ])
+        .unwrap()
+        .disassemble();
+
+        assert_eq!(
+            code(&disassembly),
+            [
+                "r0 = 0",
+                "r0 = r0 + 1",
+                "r1 = r0 > 9",
+                "if r0 > 9 goto 5",
+                "goto 1",
+                "goto 26 (halt)",
+            ]
+        );
+
+        let blocks = disassembly
+            .blocks
+            .iter()
+            .map(|b| (b.start, b.end, b.exits.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            blocks,
+            [
+                (0, 1, vec![Exit::Block(1)]),
+                (1, 4, vec![Exit::Block(2), Exit::Block(3)]),
+                (4, 5, vec![Exit::Block(1)]),
+                (5, 6, vec![Exit::Halt]),
+            ]
+        );
+        assert!(disassembly
+            .to_dot()
+            .contains("    b1 -> b3;\n    b2 -> b1;\n"));
+    }
+
+    #[test]
+    fn comparisons_that_overwrite_an_operand() {
+        // From day 19, where r2 only holds 0 or 1 by the time it's added to the ip.
+        let disassembly = example_program(&[
+            "#ip 1",
+            "mulr 5 3 2",
+            "eqrr 2 4 2",
+            "addr 2 1 1",
+            "addi 1 1 1",
+        ])
+        .unwrap()
+        .disassemble();
+
+        assert_eq!(
+            code(&disassembly),
+            [
+                "r2 = r5 * r3",
+                "r2 = r2 == r4",
+                "if r2 goto 4",
+                "goto 5 (halt)",
+            ]
+        );
+        assert_eq!(disassembly.lines[2].flow, Flow::Branch(4));
+    }
+
+    #[test]
+    fn comparisons_added_to_a_constant() {
+        // Jumps to 4 or 5, rather than skipping over 3.
+        let disassembly = example_program(&[
+            "#ip 5",
+            "seti 3 0 0",
+            "gtri 0 2 1",
+            "addi 1 3 5",
+            "seti 0 0 2",
+            "seti 1 0 2",
+            "seti 2 0 2",
+        ])
+        .unwrap()
+        .disassemble();
+
+        assert_eq!(code(&disassembly)[2], "goto r1 + 4");
+        assert_eq!(disassembly.lines[2].flow, Flow::Computed);
+        assert_eq!(disassembly.blocks[0].exits, [Exit::Unknown]);
+    }
+
+    #[test]
+    fn computed_jumps() {
+        // The example from day 19.
+        let disassembly = example_program(&[
+            "#ip 0",
+            "seti 5 0 1",
+            "seti 6 0 2",
+            "addi 0 1 0",
+            "addr 1 2 3",
+            "setr 1 0 0",
+            "seti 8 0 4",
+            "seti 9 0 5",
+        ])
+        .unwrap()
+        .disassemble();
+
+        assert_eq!(
+            code(&disassembly)[2..5],
+            ["goto 4", "r3 = r1 + r2", "goto r1 + 1"]
+        );
+        assert_eq!(disassembly.blocks[2].exits, [Exit::Unknown]);
+        assert_eq!(disassembly.blocks[3].exits, [Exit::Halt]);
+        assert!(disassembly
+            .to_dot()
+            .contains("b2 -> unknown [style=dashed]"));
+    }
+}
@@ -0,0 +1,69 @@
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+// Something that went wrong while solving a day, usually because of unexpected input.
+// The runner fills in the day if the solver didn't.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub day: Option<u32>,
+    // 1-based, like an editor shows it.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new<S: Into<String>>(message: S) -> Self {
+        Error {
+            day: None,
+            line: None,
+            message: message.into(),
+        }
+    }
+
+    // An error on the line at `idx` of the input.
+    pub fn at_line<S: Into<String>>(idx: usize, message: S) -> Self {
+        Error::new(message).with_line(idx)
+    }
+
+    pub fn with_day(mut self, day: u32) -> Self {
+        self.day.get_or_insert(day);
+        self
+    }
+
+    pub fn with_line(mut self, idx: usize) -> Self {
+        self.line.get_or_insert(idx + 1);
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "Day {}: ", day)?;
+        }
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::new(e.to_string())
+    }
+}
+
+// Attaches a line number to errors from parsing a single line.
+pub trait LineContext<T> {
+    fn line(self, idx: usize) -> Result<T>;
+}
+
+impl<T> LineContext<T> for Result<T> {
+    fn line(self, idx: usize) -> Result<T> {
+        self.map_err(|e| e.with_line(idx))
+    }
+}
@@ -0,0 +1,42 @@
+// suppress for the whole module with inner attribute...
+#![allow(non_snake_case)]
+
+#[macro_use]
+extern crate itertools;
+
+pub mod answer;
+pub mod cancel;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod emulator;
+pub mod error;
+pub mod solver;
+pub mod utils;
+
+pub use answer::Answer;
+pub use error::{Error, Result};
+pub use solver::{Part, Registry, Solver};
+pub use utils::Input;
@@ -1,111 +1,456 @@
-// suppress for the whole module with inner attribute...
-#![allow(non_snake_case)]
+use bench::{Baseline, Samples};
+use cli::Options;
+use report::{DayResult, Format};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use timings::Timings;
+use verify::{Answers, Status};
+use AdventOfCode2018::cancel;
+use AdventOfCode2018::emulator::{Debugger, Emulator, Program};
+use AdventOfCode2018::error::{self, Error};
+use AdventOfCode2018::utils::{self, Input, InputSource};
+use AdventOfCode2018::{Answer, Part, Registry, Solver};
 
-#[macro_use]
-extern crate itertools;
+mod bench;
+mod cli;
+mod parallel;
+mod report;
+mod timings;
+mod verify;
 
-use std::ops::AddAssign;
-use std::time::{Duration, Instant};
+fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    if options.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
 
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day2;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-
-pub mod emulator;
-pub mod utils;
-
-fn main() -> std::io::Result<()> {
-    println!("\n🎅🎅🎅🎅🎅 ADVENT OF CODE 2018 🎅🎅🎅🎅🎅\n");
-
-    let mut timings: Timings = Timings(Duration::new(0, 0), Duration::new(0, 0));
-
-    timings += time_day(day1::day1, 1)?;
-    timings += time_day(day2::day2, 2)?;
-    timings += time_day(day3::day3, 3)?;
-    timings += time_day(day4::day4, 4)?;
-    timings += time_day(day5::day5, 5)?;
-    timings += time_day(day6::day6, 6)?;
-    timings += time_day(day7::day7, 7)?;
-    timings += time_day(day8::day8, 8)?;
-    timings += time_day(day9::day9, 9)?;
-    timings += time_day(day10::day10, 10)?;
-    timings += time_day(day11::day11, 11)?;
-    timings += time_day(day12::day12, 12)?;
-    timings += time_day(day13::day13, 13)?;
-    timings += time_day(day14::day14, 14)?;
-    timings += time_day(day15::day15, 15)?;
-    timings += time_day(day16::day16, 16)?;
-    timings += time_day(day17::day17, 17)?;
-    timings += time_day(day18::day18, 18)?;
-    timings += time_day(day19::day19, 19)?;
-    timings += time_day(day20::day20, 20)?;
-    timings += time_day(day21::day21, 21)?;
-    timings += time_day(day22::day22, 22)?;
-    timings += time_day(day23::day23, 23)?;
-    timings += time_day(day24::day24, 24)?;
-    timings += time_day(day25::day25, 25)?;
+    let registry = Arc::new(Registry::with_all_days());
 
-    println!("*************************************\n");
-    println!(
-        "IO = {:?}, CPU = {:?}, Total = {:?}",
-        timings.0,
-        timings.1,
-        timings.0 + timings.1
+    if options.list {
+        for day in registry.days() {
+            let solver = registry.get(day).unwrap();
+            println!("Day {:02}: {}", day, solver.title());
+        }
+        return;
+    }
+
+    let days: Vec<u32> = if options.days.is_empty() {
+        registry.days()
+    } else {
+        options.days.clone()
+    };
+
+    if let Some(day) = days.iter().find(|&&d| !registry.contains(d)) {
+        eprintln!("error: Day {} does not exist", day);
+        process::exit(1);
+    }
+
+    let inputs_dir = utils::inputs_dir(options.inputs_dir.as_deref());
+    let source = match options.input.as_deref() {
+        Some("-") => InputSource::Stdin,
+        Some(path) => InputSource::File(path.into()),
+        None => InputSource::Dir(inputs_dir.clone()),
+    };
+
+    if options.disassemble {
+        disassemble(days[0], &source, options.dot);
+        return;
+    }
+
+    if options.debug {
+        debug(days[0], &source);
+        return;
+    }
+
+    if let Some(runs) = options.bench {
+        bench(&registry, &days, runs, &source, &options);
+        return;
+    }
+
+    // Statuses are reported whenever there are accepted answers, but only --verify requires them.
+    // They're for the inputs in `inputs_dir`, so a day run on another input has nothing to check.
+    let answers = match Answers::load(&verify::answers_path(&inputs_dir)) {
+        _ if options.input.is_some() => None,
+        Ok(answers) => Some(answers),
+        Err(e) if options.verify => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+        Err(_) => None,
+    };
+
+    let text = options.format == Format::Text;
+    if text && !options.verify {
+        println!("\n🎅🎅🎅🎅🎅 ADVENT OF CODE 2018 🎅🎅🎅🎅🎅\n");
+    }
+
+    let wall = Instant::now();
+    let mut results = Vec::new();
+    parallel::run_ordered(
+        &days,
+        options.jobs,
+        |&day| run_day_with_timeout(&registry, day, &source, &options.parts, options.timeout),
+        |result| {
+            if text && !options.verify {
+                print_day(&result);
+            }
+            results.push(result);
+        },
     );
+    let wall = wall.elapsed();
+
+    match options.format {
+        Format::Text if options.verify => print_verify(&results, answers.as_ref().unwrap()),
+        Format::Text => print_totals(&results, wall, options.jobs),
+        format => print!("{}", report::render(format, &results, answers.as_ref())),
+    }
+
+    if options.verify {
+        let answers = answers.as_ref().unwrap();
+        let failed = results.iter().any(|result| {
+            result.answers.iter().any(|(part, answer)| {
+                let status = result.status(*part, answer, Some(answers));
+                status == Status::Fail || status == Status::Timeout || status == Status::Error
+            })
+        });
+        if failed {
+            process::exit(1);
+        }
+    }
+
+    // Every day still gets its turn, but a failed or timed out one fails the run.
+    if results
+        .iter()
+        .any(|result| result.error.is_some() || result.timed_out)
+    {
+        process::exit(1);
+    }
+}
+
+// How long a cancelled day gets to stop before it's left running in the background.
+const CANCEL_GRACE: Duration = Duration::from_millis(100);
+
+// Runs a day on its own thread and gives up on it after `timeout`.
+// A day that times out is cancelled, and waited on for a little while so it doesn't slow down
+// the days after it. One that never calls `cancel::check` can't be stopped, so it's abandoned.
+fn run_day_with_timeout(
+    registry: &Arc<Registry>,
+    day: u32,
+    source: &InputSource,
+    parts: &[Part],
+    timeout: Option<Duration>,
+) -> DayResult {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return run_day(registry.get(day).unwrap().as_mut(), source, parts),
+    };
+
+    let start = Instant::now();
+    let (sender, receiver) = mpsc::channel();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let worker = {
+        let (registry, source, parts) = (Arc::clone(registry), source.clone(), parts.to_vec());
+        let cancelled = Arc::clone(&cancelled);
+        thread::spawn(move || {
+            cancel::install(cancelled);
+            let mut solver = registry.get(day).unwrap();
+            // Nobody is listening if the day already timed out.
+            let _ = sender.send(run_day(solver.as_mut(), &source, &parts));
+        })
+    };
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => {
+            cancelled.store(true, Ordering::Relaxed);
+            // Whatever it stopped with is thrown away, panics included.
+            let stopped = !matches!(
+                receiver.recv_timeout(CANCEL_GRACE),
+                Err(RecvTimeoutError::Timeout)
+            );
+            if stopped {
+                let _ = worker.join();
+            }
+            let title = registry.get(day).unwrap().title();
+            DayResult::timed_out(day, title, parts, start.elapsed())
+        }
+        // The solver panicked, so pass the panic along.
+        Err(RecvTimeoutError::Disconnected) => match worker.join() {
+            Err(panic) => std::panic::resume_unwind(panic),
+            Ok(()) => unreachable!("Day {} finished without a result", day),
+        },
+    }
+}
+
+// Runs a day, stopping at the first error. Parts that didn't get to run are left unsolved.
+fn run_day(solver: &mut dyn Solver, source: &InputSource, parts: &[Part]) -> DayResult {
+    let day = solver.day();
+    let mut timings = Timings::new(&format!("Day {}", day));
+    let mut answers: Vec<_> = parts.iter().map(|&part| (part, Answer::Unsolved)).collect();
+
+    let error = match timings.time(timings::IO, || utils::read_input(source, day)) {
+        Ok(input) => {
+            let mut cpu = Timings::new(timings::CPU);
+            let solved = solve(solver, &input, &mut cpu, &mut answers);
+            timings.push(cpu);
+            solved.err()
+        }
+        Err(e) => Some(Error::new(format!("failed to read input: {}", e))),
+    };
 
+    DayResult {
+        day,
+        title: solver.title(),
+        answers,
+        timings,
+        timed_out: false,
+        error: error.map(|e| e.with_day(day)),
+    }
+}
+
+fn solve(
+    solver: &mut dyn Solver,
+    input: &Input,
+    cpu: &mut Timings,
+    answers: &mut [(Part, Answer)],
+) -> error::Result<()> {
+    cpu.time(timings::PARSE, || solver.parse(input))?;
+    for (part, answer) in answers {
+        *answer = cpu.time(timings::part(*part), || solver.run(*part, input))?;
+    }
     Ok(())
 }
 
-fn time_day(day_fn: fn(&mut Vec<String>) -> (), day: i32) -> std::io::Result<Timings> {
-    println!("----- Day {:02} -----", day);
+fn print_day(result: &DayResult) {
+    println!("----- Day {:02}: {} -----", result.day, result.title);
 
-    let now = Instant::now();
-    let mut lines = utils::read_day(day)?;
-    let io = now.elapsed();
+    if result.timed_out {
+        println!("TIMEOUT after {:.2?}\n", result.timings.duration);
+        return;
+    }
 
-    let now = Instant::now();
-    day_fn(&mut lines);
-    let cpu = now.elapsed();
+    for (part, answer) in result.answers.iter().filter(|(_, a)| !result.failed(a)) {
+        if answer.is_multiline() {
+            println!("Part {}:\n{}", part, answer);
+        } else {
+            println!("Part {}: {}", part, answer);
+        }
+    }
+
+    if let Some(e) = &result.error {
+        println!("ERROR: {}", e);
+    }
 
     println!("------------------");
+    println!("{}", result.timings);
+}
+
+// Prints a row per day with the time spent in each phase, followed by the totals.
+fn print_totals(results: &[DayResult], wall: Duration, jobs: usize) {
+    let columns = [
+        ("IO", vec![timings::IO]),
+        ("Parse", vec![timings::CPU, timings::PARSE]),
+        ("Part a", vec![timings::CPU, timings::part(Part::A)]),
+        ("Part b", vec![timings::CPU, timings::part(Part::B)]),
+        ("CPU", vec![timings::CPU]),
+        ("Total", vec![]),
+    ];
+
+    let print_row = |name: &str, timings: &Timings| {
+        let mut row = format!("{:<8}", name);
+        for (_, path) in &columns {
+            let cell = timings
+                .duration_of(path)
+                .map_or("-".to_string(), |d| format!("{:.2?}", d));
+            row += &format!("{:>12}", cell);
+        }
+        println!("{}", row);
+    };
+
+    println!("*************************************\n");
+
+    let mut header = format!("{:<8}", "Day");
+    for (name, _) in &columns {
+        header += &format!("{:>12}", name);
+    }
+    println!("{}", header);
+
+    let mut total = Timings::new("Total");
+    for result in results {
+        print_row(&format!("Day {:02}", result.day), &result.timings);
+        total += &result.timings;
+    }
+    print_row("Total", &total);
+
+    // Days are timed on the wall clock, so days sharing a core count each other's time too,
+    // and the sum can be well over the wall time of the whole run. It's not CPU time.
     println!(
-        "Day {} :: IO = {:?}, CPU = {:?}, Total = {:?}\n",
-        day,
-        io,
-        cpu,
-        io + cpu
+        "\nWall = {:.2?}, sum of per-day wall time = {:.2?} ({} job{})",
+        wall,
+        total.duration,
+        jobs,
+        if jobs == 1 { "" } else { "s" }
+    );
+}
+
+// Runs each day once to warm up, then `runs` more times, and reports stats for each phase.
+// Exits non-zero if any phase regressed against the baseline.
+fn bench(registry: &Registry, days: &[u32], runs: usize, source: &InputSource, options: &Options) {
+    let baseline = options.baseline.as_ref().map(|path| {
+        Baseline::load(path).unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        })
+    });
+
+    println!(
+        "{:<6}  {:<6}  {:>10}  {:>10}  {:>10}  {:>10}  Baseline",
+        "Day", "Phase", "Min", "Median", "Mean", "Std Dev"
     );
 
-    Ok(Timings(io, cpu))
+    let mut all_samples = Vec::new();
+    let mut regressions = 0;
+
+    for &day in days {
+        let mut samples = Samples::new(day);
+        for run in 0..=runs {
+            let mut solver = registry.get(day).unwrap();
+            let result = run_day(solver.as_mut(), source, &options.parts);
+            if let Some(e) = result.error {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+            if run > 0 {
+                samples.add(&result.timings);
+            }
+        }
+
+        for (phase, stats) in samples.stats() {
+            let compared = match baseline.as_ref().and_then(|b| b.get(day, phase)) {
+                Some(previous) => {
+                    let change = bench::change(stats.median, previous);
+                    if change > options.threshold {
+                        regressions += 1;
+                        format!("{:.2?} ({:+.1}%) REGRESSED", previous, change)
+                    } else {
+                        format!("{:.2?} ({:+.1}%)", previous, change)
+                    }
+                }
+                None => String::new(),
+            };
+
+            let row = format!(
+                "Day {:02}  {:<6}  {:>10}  {:>10}  {:>10}  {:>10}  {}",
+                day,
+                phase,
+                format!("{:.2?}", stats.min),
+                format!("{:.2?}", stats.median),
+                format!("{:.2?}", stats.mean),
+                format!("{:.2?}", stats.stddev),
+                compared
+            );
+            println!("{}", row.trim_end());
+        }
+
+        all_samples.push(samples);
+    }
+
+    if let Some(path) = &options.save_baseline {
+        if let Err(e) = Baseline::from_samples(&all_samples).save(path) {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+        println!("\nSaved baseline to {}", path);
+    }
+
+    if regressions > 0 {
+        println!(
+            "\n{} phase(s) regressed by more than {}%",
+            regressions, options.threshold
+        );
+        process::exit(1);
+    }
 }
 
-struct Timings(Duration, Duration);
+// Loads the day's input as an emulator program, for days 19 and 21.
+fn load_program(day: u32, source: &InputSource) -> Program {
+    let program = utils::read_input(source, day)
+        .map_err(|e| Error::new(format!("failed to read input: {}", e)))
+        .and_then(|input| Program::new(input.lines()));
+
+    program.unwrap_or_else(|e| {
+        eprintln!("error: {}", e.with_day(day));
+        process::exit(1);
+    })
+}
+
+fn disassemble(day: u32, source: &InputSource, dot: bool) {
+    let disassembly = load_program(day, source).disassemble();
+    if dot {
+        print!("{}", disassembly.to_dot());
+    } else {
+        print!("{}", disassembly);
+    }
+}
+
+fn debug(day: u32, source: &InputSource) {
+    let program = load_program(day, source);
+    let mut debugger = Debugger::new(&program, Emulator::new());
+    if let Err(e) = debugger.run(std::io::stdin().lock(), std::io::stdout()) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+// Prints a table checking every answer against the accepted ones.
+fn print_verify(results: &[DayResult], answers: &Answers) {
+    let (mut passed, mut failed, mut unknown) = (0, 0, 0);
+
+    println!(
+        "{:<6}  {:<4}  {:<7}  {:<30}  Expected",
+        "Day", "Part", "Status", "Answer"
+    );
+    for result in results {
+        for (part, answer) in &result.answers {
+            let status = result.status(*part, answer, Some(answers));
+            match status {
+                Status::Pass => passed += 1,
+                Status::Fail | Status::Timeout | Status::Error => failed += 1,
+                Status::Unknown => unknown += 1,
+            }
+
+            let row = format!(
+                "Day {:02}  {:<4}  {:<7}  {:<30}  {}",
+                result.day,
+                part,
+                status,
+                verify::summarize(&answer.to_string()),
+                answers
+                    .get(result.day, *part)
+                    .map_or(String::new(), verify::summarize)
+            );
+            println!("{}", row.trim_end());
+        }
+    }
+
+    println!(
+        "\n{} passed, {} failed, {} without an accepted answer",
+        passed, failed, unknown
+    );
 
-impl AddAssign for Timings {
-    fn add_assign(&mut self, rhs: Timings) {
-        self.0 += rhs.0;
-        self.1 += rhs.1;
+    for e in results.iter().filter_map(|result| result.error.as_ref()) {
+        println!("{}", e);
     }
 }
@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+// Runs `job` for every item on a pool of `workers` threads.
+// `done` is called on the calling thread with each result in the order of `items`,
+// as soon as that result and all the ones before it are ready.
+pub fn run_ordered<T, R, J, D>(items: &[T], workers: usize, job: J, mut done: D)
+where
+    T: Sync,
+    R: Send,
+    J: Fn(&T) -> R + Sync,
+    D: FnMut(R),
+{
+    if workers <= 1 {
+        items.iter().map(&job).for_each(done);
+        return;
+    }
+
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..workers.min(items.len()) {
+            let sender = sender.clone();
+            let (next, job) = (&next, &job);
+            scope.spawn(move || loop {
+                let idx = next.fetch_add(1, Ordering::SeqCst);
+                if idx >= items.len() {
+                    break;
+                }
+                if sender.send((idx, job(&items[idx]))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        // Results arrive in whatever order they finish, so hold on to them until it's their turn.
+        let mut pending = BTreeMap::new();
+        let mut expected = 0;
+        for (idx, result) in receiver {
+            pending.insert(idx, result);
+            while let Some(result) = pending.remove(&expected) {
+                done(result);
+                expected += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn results_come_back_in_order() {
+        // The earlier items take the longest, so they finish last.
+        let items = [40, 30, 20, 10, 0];
+        for workers in [1, 2, 5, 8] {
+            let mut results = Vec::new();
+            run_ordered(
+                &items,
+                workers,
+                |&ms| {
+                    thread::sleep(Duration::from_millis(ms));
+                    ms
+                },
+                |ms| results.push(ms),
+            );
+            assert_eq!(results, items);
+        }
+    }
+}
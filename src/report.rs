@@ -0,0 +1,282 @@
+use crate::timings::{self, Timings};
+use crate::verify::{Answers, Status};
+use serde_json::json;
+use std::time::Duration;
+use AdventOfCode2018::answer::Answer;
+use AdventOfCode2018::error::Error;
+use AdventOfCode2018::solver::Part;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Format {
+    // The human-readable output.
+    Text,
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "markdown" | "md" => Ok(Format::Markdown),
+            _ => Err(format!(
+                "invalid format `{}`, expected `text`, `json`, `csv` or `markdown`",
+                s
+            )),
+        }
+    }
+}
+
+// The answers and timings from running a single day.
+pub struct DayResult {
+    pub day: u32,
+    pub title: &'static str,
+    pub answers: Vec<(Part, Answer)>,
+    pub timings: Timings,
+    pub timed_out: bool,
+    // Why the day stopped early. Any part it didn't get to is left unsolved.
+    pub error: Option<Error>,
+}
+
+impl DayResult {
+    // A day that was given up on after running for `elapsed`. Every part is left unsolved.
+    pub fn timed_out(day: u32, title: &'static str, parts: &[Part], elapsed: Duration) -> Self {
+        let mut timings = Timings::new(&format!("Day {}", day));
+        timings.duration = elapsed;
+
+        DayResult {
+            day,
+            title,
+            answers: parts.iter().map(|&part| (part, Answer::Unsolved)).collect(),
+            timings,
+            timed_out: true,
+            error: None,
+        }
+    }
+
+    pub fn status(&self, part: Part, answer: &Answer, answers: Option<&Answers>) -> Status {
+        match answers {
+            _ if self.timed_out => Status::Timeout,
+            _ if self.failed(answer) => Status::Error,
+            Some(answers) => answers.check(self.day, part, answer),
+            None => Status::Unknown,
+        }
+    }
+
+    // Whether a part was left unsolved because the day failed.
+    pub fn failed(&self, answer: &Answer) -> bool {
+        self.error.is_some() && *answer == Answer::Unsolved
+    }
+}
+
+// A single part of a day, flattened for the machine-readable formats.
+struct Record<'a> {
+    day: u32,
+    title: &'a str,
+    part: Part,
+    answer: &'a Answer,
+    status: Status,
+    error: Option<&'a Error>,
+    io: Duration,
+    parse: Duration,
+    time: Duration,
+}
+
+fn records<'a>(results: &'a [DayResult], answers: Option<&Answers>) -> Vec<Record<'a>> {
+    let mut records = Vec::new();
+
+    for result in results {
+        let phase = |path: &[&str]| result.timings.duration_of(path).unwrap_or_default();
+
+        for (part, answer) in &result.answers {
+            records.push(Record {
+                day: result.day,
+                title: result.title,
+                part: *part,
+                answer,
+                status: result.status(*part, answer, answers),
+                error: result.error.as_ref().filter(|_| result.failed(answer)),
+                io: phase(&[timings::IO]),
+                parse: phase(&[timings::CPU, timings::PARSE]),
+                time: phase(&[timings::CPU, timings::part(*part)]),
+            });
+        }
+    }
+
+    records
+}
+
+// Renders one record per day and part. `Format::Text` is printed by the runner itself.
+pub fn render(format: Format, results: &[DayResult], answers: Option<&Answers>) -> String {
+    let records = records(results, answers);
+    match format {
+        Format::Text => String::new(),
+        Format::Json => json(&records),
+        Format::Csv => csv(&records),
+        Format::Markdown => markdown(&records),
+    }
+}
+
+fn json(records: &[Record]) -> String {
+    let values: Vec<serde_json::Value> = records
+        .iter()
+        .map(|r| {
+            let answer = match r.answer {
+                Answer::Int(n) => json!(n),
+                Answer::Unsolved => json!(null),
+                answer => json!(answer.to_string()),
+            };
+            json!({
+                "day": r.day,
+                "title": r.title,
+                "part": r.part.to_string(),
+                "answer": answer,
+                "status": r.status.name(),
+                "error": r.error.map(Error::to_string),
+                "timings_ns": {
+                    "io": r.io.as_nanos() as u64,
+                    "parse": r.parse.as_nanos() as u64,
+                    "part": r.time.as_nanos() as u64,
+                },
+            })
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&values).unwrap() + "\n"
+}
+
+fn csv(records: &[Record]) -> String {
+    let mut out = String::from("day,title,part,answer,status,error,io_ns,parse_ns,part_ns\n");
+    for r in records {
+        out += &format!(
+            "{},{},{},{},{},{},{},{},{}\n",
+            r.day,
+            csv_field(r.title),
+            r.part,
+            csv_field(&r.answer.to_string()),
+            r.status.name(),
+            r.error.map_or(String::new(), |e| csv_field(&e.to_string())),
+            r.io.as_nanos(),
+            r.parse.as_nanos(),
+            r.time.as_nanos()
+        );
+    }
+    out
+}
+
+// Quotes a field if it contains anything that would break the row apart.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn markdown(records: &[Record]) -> String {
+    let mut out =
+        String::from("| Day | Title | Part | Answer | Status | IO | Parse | Part time |\n");
+    out += "|----:|-------|:----:|--------|:------:|---:|------:|----------:|\n";
+    for r in records {
+        // Multi-line answers are kept on one row, in a code span per line.
+        let answer = r
+            .answer
+            .to_string()
+            .lines()
+            .map(|line| format!("`{}`", line))
+            .collect::<Vec<_>>()
+            .join("<br>");
+        out += &format!(
+            "| {} | {} | {} | {} | {} | {:.2?} | {:.2?} | {:.2?} |\n",
+            r.day,
+            r.title.replace('|', "\\|"),
+            r.part,
+            answer.replace('|', "\\|"),
+            r.status,
+            r.io,
+            r.parse,
+            r.time
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(answers: Vec<(Part, Answer)>) -> DayResult {
+        let mut cpu = Timings::new(timings::CPU);
+        cpu.push(Timings::leaf("part a", Duration::from_nanos(1500)));
+        let mut timings = Timings::new("Day 10");
+        timings.push(cpu);
+
+        DayResult {
+            day: 10,
+            title: "The \"Stars\", Aligned",
+            answers,
+            timings,
+            timed_out: false,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("1,2"), "\"1,2\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("#.\n.#"), "\"#.\n.#\"");
+        assert_eq!(csv_field("#.\r\n.#"), "\"#.\r\n.#\"");
+    }
+
+    #[test]
+    fn csv_keeps_one_record_per_part() {
+        let results = [result(vec![
+            (Part::A, Answer::Art("#.\n.#".to_string())),
+            (Part::B, Answer::Coord(3, 4)),
+        ])];
+
+        assert_eq!(
+            render(Format::Csv, &results, None),
+            "day,title,part,answer,status,error,io_ns,parse_ns,part_ns\n\
+             10,\"The \"\"Stars\"\", Aligned\",a,\"#.\n.#\",unknown,,0,0,1500\n\
+             10,\"The \"\"Stars\"\", Aligned\",b,\"3,4\",unknown,,0,0,0\n"
+        );
+    }
+
+    #[test]
+    fn csv_reports_errors_on_the_parts_they_stopped() {
+        let mut result = result(vec![(Part::A, Answer::Int(3)), (Part::B, Answer::Unsolved)]);
+        result.error = Some(Error::at_line(2, "expected `x=`, got `y=`").with_day(10));
+
+        assert_eq!(
+            render(Format::Csv, &[result], None),
+            "day,title,part,answer,status,error,io_ns,parse_ns,part_ns\n\
+             10,\"The \"\"Stars\"\", Aligned\",a,3,unknown,,0,0,1500\n\
+             10,\"The \"\"Stars\"\", Aligned\",b,unsolved,error,\"Day 10: line 3: expected `x=`, got `y=`\",0,0,0\n"
+        );
+    }
+
+    #[test]
+    fn json_escapes_text() {
+        let results = [result(vec![
+            (Part::A, Answer::Text("a \"quote\"\\".to_string())),
+            (Part::B, Answer::Art("#.\n.#".to_string())),
+        ])];
+        let json = render(Format::Json, &results, None);
+
+        assert!(json.contains(r#""answer": "a \"quote\"\\""#));
+        assert!(json.contains(r##""answer": "#.\n.#""##));
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0]["title"], "The \"Stars\", Aligned");
+        assert_eq!(parsed[0]["timings_ns"]["part"], 1500);
+        assert_eq!(parsed[1]["answer"], "#.\n.#");
+        assert_eq!(parsed[1]["error"], serde_json::Value::Null);
+    }
+}
@@ -0,0 +1,150 @@
+use crate::answer::Answer;
+use crate::error::Result;
+use crate::utils::Input;
+use crate::{
+    day1, day10, day11, day12, day13, day14, day15, day16, day17, day18, day19, day2, day20, day21,
+    day22, day23, day24, day25, day3, day4, day5, day6, day7, day8, day9,
+};
+use std::collections::BTreeMap;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
+    A,
+    B,
+}
+
+impl Part {
+    pub fn all() -> Vec<Part> {
+        vec![Part::A, Part::B]
+    }
+}
+
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Part::A => f.pad("a"),
+            Part::B => f.pad("b"),
+        }
+    }
+}
+
+// A solution to a single day's puzzle.
+// A new solver is created for every run, so anything derived from the input
+// can be kept on the solver and shared between both parts.
+// Loops that might run for a long time, or forever on bad input, should call `cancel::check`.
+pub trait Solver {
+    fn day(&self) -> u32;
+
+    fn title(&self) -> &'static str;
+
+    // Called once with the puzzle input, before either part runs.
+    fn parse(&mut self, _input: &Input) -> Result<()> {
+        Ok(())
+    }
+
+    fn part_a(&mut self, input: &Input) -> Result<Answer>;
+
+    fn part_b(&mut self, input: &Input) -> Result<Answer>;
+
+    fn run(&mut self, part: Part, input: &Input) -> Result<Answer> {
+        match part {
+            Part::A => self.part_a(input),
+            Part::B => self.part_b(input),
+        }
+    }
+}
+
+// Runs a part of an example from a puzzle statement, given one line per entry, on a new solver.
+#[cfg(test)]
+pub fn run_example<S: Solver + Default>(part: Part, lines: &[&str]) -> Answer {
+    run_example_with(S::default(), part, lines)
+}
+
+// Like `run_example`, for a solver that's set up differently for the examples.
+#[cfg(test)]
+pub fn run_example_with<S: Solver>(mut solver: S, part: Part, lines: &[&str]) -> Answer {
+    let input = Input::new(lines.iter().map(|l| l.to_string()).collect());
+    solver.parse(&input).unwrap();
+    solver.run(part, &input).unwrap()
+}
+
+pub type Factory = Box<dyn Fn() -> Box<dyn Solver> + Send + Sync>;
+
+// Solvers available to the runner, keyed by day.
+// Registering a solver for a day that already has one replaces it.
+pub struct Registry {
+    factories: BTreeMap<u32, Factory>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry {
+            factories: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_all_days() -> Self {
+        let mut registry = Registry::new();
+        registry.register_default::<day1::Day1>();
+        registry.register_default::<day2::Day2>();
+        registry.register_default::<day3::Day3>();
+        registry.register_default::<day4::Day4>();
+        registry.register_default::<day5::Day5>();
+        registry.register_default::<day6::Day6>();
+        registry.register_default::<day7::Day7>();
+        registry.register_default::<day8::Day8>();
+        registry.register_default::<day9::Day9>();
+        registry.register_default::<day10::Day10>();
+        registry.register_default::<day11::Day11>();
+        registry.register_default::<day12::Day12>();
+        registry.register_default::<day13::Day13>();
+        registry.register_default::<day14::Day14>();
+        registry.register_default::<day15::Day15>();
+        registry.register_default::<day16::Day16>();
+        registry.register_default::<day17::Day17>();
+        registry.register_default::<day18::Day18>();
+        registry.register_default::<day19::Day19>();
+        registry.register_default::<day20::Day20>();
+        registry.register_default::<day21::Day21>();
+        registry.register_default::<day22::Day22>();
+        registry.register_default::<day23::Day23>();
+        registry.register_default::<day24::Day24>();
+        registry.register_default::<day25::Day25>();
+        registry
+    }
+
+    pub fn register<F>(&mut self, factory: F)
+    where
+        F: Fn() -> Box<dyn Solver> + Send + Sync + 'static,
+    {
+        let day = factory().day();
+        self.factories.insert(day, Box::new(factory));
+    }
+
+    pub fn register_default<S>(&mut self)
+    where
+        S: Solver + Default + 'static,
+    {
+        self.register(|| Box::new(S::default()));
+    }
+
+    // Creates a new solver for the given day.
+    pub fn get(&self, day: u32) -> Option<Box<dyn Solver>> {
+        self.factories.get(&day).map(|factory| factory())
+    }
+
+    pub fn contains(&self, day: u32) -> bool {
+        self.factories.contains_key(&day)
+    }
+
+    // All registered days, in order.
+    pub fn days(&self) -> Vec<u32> {
+        self.factories.keys().cloned().collect()
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
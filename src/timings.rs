@@ -0,0 +1,116 @@
+use std::fmt;
+use std::ops::AddAssign;
+use std::time::{Duration, Instant};
+use AdventOfCode2018::solver::Part;
+
+pub const IO: &str = "io";
+pub const CPU: &str = "cpu";
+pub const PARSE: &str = "parse";
+
+// The phase that a part of a day is recorded under.
+pub const fn part(part: Part) -> &'static str {
+    match part {
+        Part::A => "part a",
+        Part::B => "part b",
+    }
+}
+
+// How long something took, broken down into the phases it's made of.
+// A day looks like:
+//
+//   Day 14
+//     io
+//     cpu
+//       parse
+//       part a
+//       part b
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Timings {
+    pub name: String,
+    pub duration: Duration,
+    pub phases: Vec<Timings>,
+}
+
+impl Timings {
+    pub fn new(name: &str) -> Self {
+        Timings {
+            name: name.to_string(),
+            duration: Duration::default(),
+            phases: Vec::new(),
+        }
+    }
+
+    pub fn leaf(name: &str, duration: Duration) -> Self {
+        Timings {
+            duration,
+            ..Timings::new(name)
+        }
+    }
+
+    // Adds a phase, which counts towards this one's duration.
+    pub fn push(&mut self, phase: Timings) {
+        self.duration += phase.duration;
+        self.phases.push(phase);
+    }
+
+    // Runs `f`, recording how long it took as a new phase.
+    pub fn time<T, F>(&mut self, name: &str, f: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        let now = Instant::now();
+        let result = f();
+        self.push(Timings::leaf(name, now.elapsed()));
+        result
+    }
+
+    // Finds a phase by its path from here, e.g. `["cpu", "part a"]`.
+    pub fn get(&self, path: &[&str]) -> Option<&Timings> {
+        match path.split_first() {
+            None => Some(self),
+            Some((name, rest)) => self
+                .phases
+                .iter()
+                .find(|phase| phase.name == *name)
+                .and_then(|phase| phase.get(rest)),
+        }
+    }
+
+    pub fn duration_of(&self, path: &[&str]) -> Option<Duration> {
+        self.get(path).map(|phase| phase.duration)
+    }
+
+    fn fmt_depth(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "{:indent$}{} = {:?}",
+            "",
+            self.name,
+            self.duration,
+            indent = depth * 2
+        )?;
+        for phase in &self.phases {
+            phase.fmt_depth(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+// Merges the phases of two trees by name, keeping the order they were first seen in.
+impl AddAssign<&Timings> for Timings {
+    fn add_assign(&mut self, rhs: &Timings) {
+        self.duration += rhs.duration;
+        for phase in &rhs.phases {
+            match self.phases.iter_mut().find(|p| p.name == phase.name) {
+                Some(existing) => *existing += phase,
+                None => self.phases.push(phase.clone()),
+            }
+        }
+    }
+}
+
+impl fmt::Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_depth(f, 0)
+    }
+}
@@ -1,56 +1,99 @@
+use crate::error::{Error, Result};
 use std::io::BufRead;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-pub fn read_day(day: i32) -> std::io::Result<Vec<String>> {
-    let f = std::fs::File::open(format!("inputs/Day{}.txt", day))?;
-    let f = std::io::BufReader::new(f);
+mod bitset;
+mod cycle;
+mod direction;
+mod geometry;
+mod grid;
+mod input;
+pub mod search;
 
-    let mut lines: Vec<String> = Vec::new();
+pub use bitset::{BitSet, FixedBitSet};
+pub use cycle::Cycle;
+pub use direction::{Direction, Direction8};
+pub use geometry::{Coord, Vec2, Vec3, Vec4};
+pub use grid::{Grid, Order, Point};
+pub use input::{FromCaptures, Input};
 
-    for line in f.lines() {
-        lines.push(line.unwrap());
-    }
-
-    Ok(lines)
-}
-
-pub fn parse<T>(string: &str) -> T
-where
-    T: FromStr + Default,
-{
-    string.trim().parse().unwrap_or_default()
-}
+pub const INPUTS_ENV: &str = "AOC_INPUTS";
 
-pub fn set_bit(bits: &mut u32, bit: usize) {
-    *bits |= 0x1 << bit;
+// Where to read a day's puzzle input from.
+#[derive(Clone, Debug)]
+pub enum InputSource {
+    // A directory holding `Day{N}.txt` for every day.
+    Dir(PathBuf),
+    File(PathBuf),
+    Stdin,
 }
 
-pub fn unset_bit(bits: &mut u32, bit: usize) {
-    *bits &= !(1 << bit);
-}
+// The directory holding the puzzle inputs. An explicit directory wins over `AOC_INPUTS`,
+// then `inputs/` in the working directory, falling back to the one in this repository.
+pub fn inputs_dir(dir: Option<&str>) -> PathBuf {
+    if let Some(dir) = dir {
+        return PathBuf::from(dir);
+    }
+    if let Some(dir) = std::env::var_os(INPUTS_ENV) {
+        return PathBuf::from(dir);
+    }
 
-pub fn set_bit_to(bits: &mut u32, bit: usize, value: bool) {
-    if value {
-        set_bit(bits, bit);
+    let local = PathBuf::from("inputs");
+    if local.is_dir() {
+        local
     } else {
-        unset_bit(bits, bit);
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs")
     }
 }
 
-pub fn get_bit(bits: &u32, bit: usize) -> u32 {
-    (*bits >> bit) & 1
+pub fn read_input(source: &InputSource, day: u32) -> std::io::Result<Input> {
+    let lines = match source {
+        InputSource::Dir(dir) => {
+            read_lines(std::fs::File::open(dir.join(format!("Day{}.txt", day)))?)
+        }
+        InputSource::File(path) => read_lines(std::fs::File::open(path)?),
+        InputSource::Stdin => read_lines(std::io::stdin()),
+    }?;
+    Ok(Input::new(lines))
 }
 
-pub fn is_bit_set(bits: &u32, bit: usize) -> bool {
-    get_bit(bits, bit) != 0
+fn read_lines<R: std::io::Read>(reader: R) -> std::io::Result<Vec<String>> {
+    std::io::BufReader::new(reader).lines().collect()
 }
 
-pub fn intersection(a: u32, b: u32) -> u32 {
-    a & b
+// Parses a value, ignoring surrounding whitespace. The error quotes the text that didn't parse.
+pub fn parse<T: FromStr>(string: &str) -> Result<T> {
+    let trimmed = string.trim();
+    trimmed.parse().map_err(|_| {
+        let name = std::any::type_name::<T>();
+        let name = name.rsplit("::").next().unwrap_or(name);
+        Error::new(format!("invalid {} `{}`", name, trimmed))
+    })
 }
 
-pub fn difference(a: u32, b: u32) -> u32 {
-    a & !b
+// Pulls every integer out of a line, skipping whatever text is around them.
+// A `-` directly before the digits makes it negative.
+pub fn ints<T: FromStr>(line: &str) -> Result<Vec<T>> {
+    let bytes = line.as_bytes();
+    let mut ints = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = if i > 0 && bytes[i - 1] == b'-' {
+            i - 1
+        } else {
+            i
+        };
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        ints.push(parse(&line[start..i])?);
+    }
+    Ok(ints)
 }
 
 pub fn wrap(v: isize, len: usize) -> usize {
@@ -61,5 +104,41 @@ pub fn wrap(v: isize, len: usize) -> usize {
     if wrapped < 0 {
         wrapped += len as isize;
     }
-    return wrapped as usize;
+    wrapped as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_is_strict_but_trims() {
+        assert_eq!(parse::<usize>(" 42\t"), Ok(42));
+        assert_eq!(parse::<i32>("+5"), Ok(5));
+        assert_eq!(parse::<i32>("-5"), Ok(-5));
+        assert_eq!(
+            parse::<usize>("-5").unwrap_err().message,
+            "invalid usize `-5`"
+        );
+        assert_eq!(
+            parse::<u8>(" 12a ").unwrap_err().message,
+            "invalid u8 `12a`"
+        );
+    }
+
+    #[test]
+    fn ints_skip_text_around_them() {
+        assert_eq!(
+            ints::<i64>("position=< 9, -3> velocity=<-1,  2>"),
+            Ok(vec![9, -3, -1, 2])
+        );
+        assert_eq!(ints::<i32>("x=495, y=2..7"), Ok(vec![495, 2, 7]));
+        // A `-` only counts when it's right before the digits.
+        assert_eq!(ints::<i32>("a-b - 3 --4"), Ok(vec![3, -4]));
+        assert_eq!(ints::<i32>("no numbers here"), Ok(vec![]));
+        assert_eq!(
+            ints::<usize>("#1 @ -3").unwrap_err().message,
+            "invalid usize `-3`"
+        );
+    }
 }
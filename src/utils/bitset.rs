@@ -0,0 +1,500 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Shl, Shr};
+
+const WORD: usize = 64;
+
+// A set of small non-negative integers, a bit each.
+// It grows to fit whatever's inserted; `with_capacity` sizes it up front.
+#[derive(Clone, Default)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        BitSet::default()
+    }
+
+    pub fn with_capacity(bits: usize) -> Self {
+        BitSet {
+            words: vec![0; bits.div_ceil(WORD)],
+        }
+    }
+
+    // The number of bits it can hold without growing.
+    pub fn capacity(&self) -> usize {
+        self.words.len() * WORD
+    }
+
+    // Returns whether the bit was newly set.
+    pub fn insert(&mut self, bit: usize) -> bool {
+        let (word, mask) = (bit / WORD, 1 << (bit % WORD));
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let added = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        added
+    }
+
+    // Returns whether the bit was set.
+    pub fn remove(&mut self, bit: usize) -> bool {
+        let (word, mask) = (bit / WORD, 1 << (bit % WORD));
+        match self.words.get_mut(word) {
+            Some(w) => {
+                let removed = *w & mask != 0;
+                *w &= !mask;
+                removed
+            }
+            None => false,
+        }
+    }
+
+    pub fn set(&mut self, bit: usize, value: bool) {
+        if value {
+            self.insert(bit);
+        } else {
+            self.remove(bit);
+        }
+    }
+
+    pub fn contains(&self, bit: usize) -> bool {
+        contains(&self.words, bit)
+    }
+
+    // The number of bits set.
+    pub fn len(&self) -> usize {
+        count(&self.words)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|w| *w = 0);
+    }
+
+    // The set bits, from lowest to highest.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        iter(&self.words)
+    }
+
+    // `len` bits starting from `start`, as an integer with `start` as its lowest bit.
+    pub fn window(&self, start: usize, len: usize) -> u64 {
+        window(&self.words, start, len)
+    }
+
+    pub fn is_subset(&self, other: &BitSet) -> bool {
+        is_subset(&self.words, &other.words)
+    }
+
+    pub fn union_with(&mut self, other: &BitSet) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (w, &o) in self.words.iter_mut().zip(&other.words) {
+            *w |= o;
+        }
+    }
+
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        for (i, w) in self.words.iter_mut().enumerate() {
+            *w &= other.words.get(i).copied().unwrap_or(0);
+        }
+    }
+
+    pub fn difference_with(&mut self, other: &BitSet) {
+        for (w, &o) in self.words.iter_mut().zip(&other.words) {
+            *w &= !o;
+        }
+    }
+
+    pub fn union(&self, other: &BitSet) -> BitSet {
+        let mut union = self.clone();
+        union.union_with(other);
+        union
+    }
+
+    pub fn intersection(&self, other: &BitSet) -> BitSet {
+        let mut intersection = self.clone();
+        intersection.intersect_with(other);
+        intersection
+    }
+
+    pub fn difference(&self, other: &BitSet) -> BitSet {
+        let mut difference = self.clone();
+        difference.difference_with(other);
+        difference
+    }
+
+    // The words without the zeros on the end, which don't change what's in the set.
+    fn trimmed(&self) -> &[u64] {
+        let len = self
+            .words
+            .iter()
+            .rposition(|&w| w != 0)
+            .map_or(0, |i| i + 1);
+        &self.words[..len]
+    }
+}
+
+// Moves every bit up by `shift`, growing to fit.
+impl Shl<usize> for &BitSet {
+    type Output = BitSet;
+
+    fn shl(self, shift: usize) -> BitSet {
+        let mut shifted = vec![0; self.words.len() + shift / WORD + 1];
+        shift_left(&self.words, shift, &mut shifted);
+        BitSet { words: shifted }
+    }
+}
+
+// Moves every bit down by `shift`, dropping the ones that go below zero.
+impl Shr<usize> for &BitSet {
+    type Output = BitSet;
+
+    fn shr(self, shift: usize) -> BitSet {
+        let mut shifted = vec![0; self.words.len()];
+        shift_right(&self.words, shift, &mut shifted);
+        BitSet { words: shifted }
+    }
+}
+
+impl PartialEq for BitSet {
+    fn eq(&self, other: &BitSet) -> bool {
+        self.trimmed() == other.trimmed()
+    }
+}
+
+impl Eq for BitSet {}
+
+impl Hash for BitSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.trimmed().hash(state);
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(bits: I) -> Self {
+        let mut set = BitSet::new();
+        bits.into_iter().for_each(|bit| {
+            set.insert(bit);
+        });
+        set
+    }
+}
+
+impl fmt::Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+// A set of integers below `64 * WORDS`, kept inline so it's `Copy` and never allocates.
+// Inserting anything past the end panics.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FixedBitSet<const WORDS: usize> {
+    words: [u64; WORDS],
+}
+
+impl<const WORDS: usize> Default for FixedBitSet<WORDS> {
+    fn default() -> Self {
+        FixedBitSet { words: [0; WORDS] }
+    }
+}
+
+impl<const WORDS: usize> FixedBitSet<WORDS> {
+    pub fn new() -> Self {
+        FixedBitSet::default()
+    }
+
+    pub fn capacity(&self) -> usize {
+        WORDS * WORD
+    }
+
+    // Returns whether the bit was newly set.
+    pub fn insert(&mut self, bit: usize) -> bool {
+        assert!(bit < WORDS * WORD, "bit {} is past the end of the set", bit);
+        let (word, mask) = (bit / WORD, 1 << (bit % WORD));
+        let added = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        added
+    }
+
+    // Returns whether the bit was set.
+    pub fn remove(&mut self, bit: usize) -> bool {
+        let removed = self.contains(bit);
+        if removed {
+            self.words[bit / WORD] &= !(1 << (bit % WORD));
+        }
+        removed
+    }
+
+    pub fn set(&mut self, bit: usize, value: bool) {
+        if value {
+            self.insert(bit);
+        } else {
+            self.remove(bit);
+        }
+    }
+
+    pub fn contains(&self, bit: usize) -> bool {
+        contains(&self.words, bit)
+    }
+
+    // The number of bits set.
+    pub fn len(&self) -> usize {
+        count(&self.words)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words = [0; WORDS];
+    }
+
+    // The set bits, from lowest to highest.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        iter(&self.words)
+    }
+
+    // `len` bits starting from `start`, as an integer with `start` as its lowest bit.
+    pub fn window(&self, start: usize, len: usize) -> u64 {
+        window(&self.words, start, len)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        is_subset(&self.words, &other.words)
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        for (w, &o) in self.words.iter_mut().zip(&other.words) {
+            *w |= o;
+        }
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        for (w, &o) in self.words.iter_mut().zip(&other.words) {
+            *w &= o;
+        }
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        for (w, &o) in self.words.iter_mut().zip(&other.words) {
+            *w &= !o;
+        }
+    }
+
+    pub fn union(mut self, other: &Self) -> Self {
+        self.union_with(other);
+        self
+    }
+
+    pub fn intersection(mut self, other: &Self) -> Self {
+        self.intersect_with(other);
+        self
+    }
+
+    pub fn difference(mut self, other: &Self) -> Self {
+        self.difference_with(other);
+        self
+    }
+}
+
+// Moves every bit up by `shift`, dropping the ones that go past the end.
+impl<const WORDS: usize> Shl<usize> for FixedBitSet<WORDS> {
+    type Output = Self;
+
+    fn shl(self, shift: usize) -> Self {
+        let mut shifted = Self::new();
+        shift_left(&self.words, shift, &mut shifted.words);
+        shifted
+    }
+}
+
+// Moves every bit down by `shift`, dropping the ones that go below zero.
+impl<const WORDS: usize> Shr<usize> for FixedBitSet<WORDS> {
+    type Output = Self;
+
+    fn shr(self, shift: usize) -> Self {
+        let mut shifted = Self::new();
+        shift_right(&self.words, shift, &mut shifted.words);
+        shifted
+    }
+}
+
+impl<const WORDS: usize> FromIterator<usize> for FixedBitSet<WORDS> {
+    fn from_iter<I: IntoIterator<Item = usize>>(bits: I) -> Self {
+        let mut set = Self::new();
+        bits.into_iter().for_each(|bit| {
+            set.insert(bit);
+        });
+        set
+    }
+}
+
+impl<const WORDS: usize> fmt::Debug for FixedBitSet<WORDS> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+// Both kinds of set are words with the lowest bits first. Words past the end read as zero.
+
+fn contains(words: &[u64], bit: usize) -> bool {
+    words
+        .get(bit / WORD)
+        .is_some_and(|w| w & (1 << (bit % WORD)) != 0)
+}
+
+fn count(words: &[u64]) -> usize {
+    words.iter().map(|w| w.count_ones() as usize).sum()
+}
+
+fn iter(words: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    words.iter().enumerate().flat_map(|(i, &w)| {
+        let mut w = w;
+        std::iter::from_fn(move || {
+            if w == 0 {
+                return None;
+            }
+            let bit = w.trailing_zeros() as usize;
+            w &= w - 1;
+            Some(i * WORD + bit)
+        })
+    })
+}
+
+fn window(words: &[u64], start: usize, len: usize) -> u64 {
+    assert!(len <= WORD, "a window holds at most {} bits", WORD);
+    if len == 0 {
+        return 0;
+    }
+    let (word, shift) = (start / WORD, start % WORD);
+    let word_at = |i: usize| words.get(i).copied().unwrap_or(0);
+
+    let mut bits = word_at(word) >> shift;
+    if shift > 0 {
+        bits |= word_at(word + 1) << (WORD - shift);
+    }
+    if len < WORD {
+        bits &= (1 << len) - 1;
+    }
+    bits
+}
+
+fn is_subset(words: &[u64], other: &[u64]) -> bool {
+    words
+        .iter()
+        .enumerate()
+        .all(|(i, &w)| w & !other.get(i).copied().unwrap_or(0) == 0)
+}
+
+// Shifts `words` up into `shifted`, dropping whatever doesn't fit.
+fn shift_left(words: &[u64], shift: usize, shifted: &mut [u64]) {
+    let (offset, bits) = (shift / WORD, shift % WORD);
+    for (i, &w) in words.iter().enumerate() {
+        if let Some(s) = shifted.get_mut(i + offset) {
+            *s |= w << bits;
+        }
+        if bits > 0 {
+            if let Some(s) = shifted.get_mut(i + offset + 1) {
+                *s |= w >> (WORD - bits);
+            }
+        }
+    }
+}
+
+// Shifts `words` down into `shifted`, dropping whatever goes below zero.
+fn shift_right(words: &[u64], shift: usize, shifted: &mut [u64]) {
+    let (offset, bits) = (shift / WORD, shift % WORD);
+    let word_at = |i: usize| words.get(i).copied().unwrap_or(0);
+    for (i, s) in shifted.iter_mut().enumerate() {
+        *s = word_at(i + offset) >> bits;
+        if bits > 0 {
+            *s |= word_at(i + offset + 1) << (WORD - bits);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grows_to_fit() {
+        let mut set = BitSet::with_capacity(10);
+        assert_eq!(set.capacity(), 64);
+
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        assert!(set.insert(200));
+        assert!(set.contains(200) && !set.contains(199));
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [3, 200]);
+
+        assert!(set.remove(200));
+        assert!(!set.remove(1000));
+        assert_eq!(set, [3].iter().copied().collect());
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: BitSet = vec![1, 2, 70].into_iter().collect();
+        let b: BitSet = vec![2, 3].into_iter().collect();
+
+        assert_eq!(a.union(&b).iter().collect::<Vec<_>>(), [1, 2, 3, 70]);
+        assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), [2]);
+        assert_eq!(a.difference(&b).iter().collect::<Vec<_>>(), [1, 70]);
+        assert!(a.intersection(&b).is_subset(&b));
+        assert!(!a.is_subset(&b));
+    }
+
+    #[test]
+    fn shifts_and_windows_cross_words() {
+        let set: BitSet = vec![0, 62, 63, 64].into_iter().collect();
+
+        assert_eq!((&set << 2).iter().collect::<Vec<_>>(), [2, 64, 65, 66]);
+        assert_eq!((&set << 64).iter().collect::<Vec<_>>(), [64, 126, 127, 128]);
+        assert_eq!((&set >> 63).iter().collect::<Vec<_>>(), [0, 1]);
+
+        assert_eq!(set.window(61, 5), 0b01110);
+        assert_eq!(set.window(0, 3), 0b001);
+        assert_eq!(set.window(500, 64), 0);
+    }
+
+    #[test]
+    fn fixed_size() {
+        let mut set = FixedBitSet::<2>::new();
+        assert_eq!(set.capacity(), 128);
+        assert!(set.insert(63) && set.insert(64) && !set.insert(64));
+        assert_eq!(set.len(), 2);
+
+        let copy = set;
+        set.remove(63);
+        assert_eq!(copy.iter().collect::<Vec<_>>(), [63, 64]);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [64]);
+
+        // Bits shifted past either end are dropped.
+        assert_eq!((copy << 64).iter().collect::<Vec<_>>(), [127]);
+        assert_eq!((copy >> 64).iter().collect::<Vec<_>>(), [0]);
+        assert_eq!(copy.window(62, 4), 0b0110);
+
+        let other: FixedBitSet<2> = vec![1, 64].into_iter().collect();
+        assert_eq!(copy.intersection(&other), set);
+        assert!(set.is_subset(&other) && !copy.is_subset(&other));
+        assert_eq!(copy.union(&other).len(), 3);
+        assert_eq!(copy.difference(&other).iter().collect::<Vec<_>>(), [63]);
+    }
+
+    #[test]
+    #[should_panic(expected = "past the end")]
+    fn fixed_size_doesnt_grow() {
+        FixedBitSet::<1>::new().insert(64);
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// A sequence of states, each stepped from the last, that ends up going round in a loop.
+// It's found by remembering every state until one comes around again,
+// so it never finishes if the states never repeat.
+pub struct Cycle<S> {
+    // Every state up to and including the first repeat.
+    states: Vec<S>,
+    start: usize,
+    length: usize,
+}
+
+impl<S: Hash + Eq + Clone> Cycle<S> {
+    pub fn find(initial: S, step: impl FnMut(&S) -> S) -> Self {
+        Cycle::find_by_key(initial, step, S::clone)
+    }
+}
+
+impl<S> Cycle<S> {
+    // Matches states by a key, for states that repeat in all but some detail,
+    // like a pattern that repeats in a different place.
+    pub fn find_by_key<K: Hash + Eq>(
+        initial: S,
+        mut step: impl FnMut(&S) -> S,
+        mut key: impl FnMut(&S) -> K,
+    ) -> Self {
+        let mut seen = HashMap::new();
+        let mut states = vec![initial];
+
+        loop {
+            let n = states.len() - 1;
+            if let Some(start) = seen.insert(key(&states[n]), n) {
+                return Cycle {
+                    states,
+                    start,
+                    length: n - start,
+                };
+            }
+            let next = step(&states[n]);
+            states.push(next);
+        }
+    }
+
+    // The first step that's part of the loop.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    // The number of steps it takes to go round the loop once.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    // The step before `n` that's at the same point in the loop.
+    pub fn index_of(&self, n: usize) -> usize {
+        if n < self.start + self.length {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+
+    // How many times the loop goes all the way round before step `n`.
+    pub fn laps(&self, n: usize) -> usize {
+        (n - self.index_of(n)) / self.length
+    }
+
+    pub fn state_at(&self, n: usize) -> &S {
+        &self.states[self.index_of(n)]
+    }
+
+    // The state that first matched one seen before, after one lap of the loop.
+    pub fn repeat(&self) -> &S {
+        self.states.last().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_loop() {
+        // 3 -> 10 -> 5 -> 16 -> 8 -> 4 -> 2 -> 1 -> 4 -> ...
+        let collatz = Cycle::find(3u64, |&n| if n % 2 == 0 { n / 2 } else { 3 * n + 1 });
+
+        assert_eq!((collatz.start(), collatz.length()), (5, 3));
+        assert_eq!(*collatz.state_at(4), 8);
+        assert_eq!(*collatz.state_at(1_000_000_000), 1);
+        assert_eq!(*collatz.state_at(1_000_000_001), 4);
+        assert_eq!(collatz.laps(11), 2);
+    }
+
+    #[test]
+    fn matches_by_key() {
+        // Counts up by 3s, so mod 4 it goes round every 4 steps from the start.
+        let counting = Cycle::find_by_key(1, |&n| n + 3, |&n| n % 4);
+
+        assert_eq!((counting.start(), counting.length()), (0, 4));
+        assert_eq!(*counting.repeat(), 13);
+        let n = 1_001;
+        let drift = counting.repeat() - counting.state_at(0);
+        assert_eq!(counting.state_at(n) + counting.laps(n) * drift, 1 + 3 * n);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::utils::{Coord, Vec2};
+use std::ops::Neg;
+
+// One of the four compass directions, on a map where north is up.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    // Clockwise, starting from north.
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        self.turn_left().turn_left()
+    }
+
+    // The vector a single step moves by.
+    pub fn delta<T: Coord + Neg<Output = T>>(self) -> Vec2<T> {
+        self.step(Vec2::zero())
+    }
+
+    pub fn step<T: Coord>(self, p: Vec2<T>) -> Vec2<T> {
+        match self {
+            Direction::North => p.up(),
+            Direction::East => p.right(),
+            Direction::South => p.down(),
+            Direction::West => p.left(),
+        }
+    }
+
+    // The step, unless it goes below zero or overflows.
+    pub fn checked_step<T: Coord>(self, p: Vec2<T>) -> Option<Vec2<T>> {
+        let (zero, one) = (T::ZERO, T::ONE);
+        match self {
+            Direction::North => p.checked_sub(Vec2::new(zero, one)),
+            Direction::East => p.checked_add(Vec2::new(one, zero)),
+            Direction::South => p.checked_add(Vec2::new(zero, one)),
+            Direction::West => p.checked_sub(Vec2::new(one, zero)),
+        }
+    }
+
+    // Parses an arrow, `^`, `>`, `v` or `<`.
+    pub fn from_arrow(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Direction::North),
+            '>' => Some(Direction::East),
+            'v' => Some(Direction::South),
+            '<' => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    pub fn to_arrow(self) -> char {
+        match self {
+            Direction::North => '^',
+            Direction::East => '>',
+            Direction::South => 'v',
+            Direction::West => '<',
+        }
+    }
+
+    // Parses a compass letter, `N`, `E`, `S` or `W`.
+    pub fn from_letter(c: char) -> Option<Self> {
+        match c {
+            'N' => Some(Direction::North),
+            'E' => Some(Direction::East),
+            'S' => Some(Direction::South),
+            'W' => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    pub fn to_letter(self) -> char {
+        match self {
+            Direction::North => 'N',
+            Direction::East => 'E',
+            Direction::South => 'S',
+            Direction::West => 'W',
+        }
+    }
+}
+
+// One of the eight compass directions, including the diagonals.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    // Clockwise, starting from north.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    fn index(self) -> usize {
+        Direction8::ALL.iter().position(|&d| d == self).unwrap()
+    }
+
+    // Turns by 45 degrees.
+    pub fn turn_left(self) -> Self {
+        Direction8::ALL[(self.index() + 7) % 8]
+    }
+
+    pub fn turn_right(self) -> Self {
+        Direction8::ALL[(self.index() + 1) % 8]
+    }
+
+    pub fn reverse(self) -> Self {
+        Direction8::ALL[(self.index() + 4) % 8]
+    }
+
+    pub fn delta<T: Coord + Neg<Output = T>>(self) -> Vec2<T> {
+        self.step(Vec2::zero())
+    }
+
+    pub fn step<T: Coord>(self, p: Vec2<T>) -> Vec2<T> {
+        match self {
+            Direction8::North => p.up(),
+            Direction8::NorthEast => p.up().right(),
+            Direction8::East => p.right(),
+            Direction8::SouthEast => p.down().right(),
+            Direction8::South => p.down(),
+            Direction8::SouthWest => p.down().left(),
+            Direction8::West => p.left(),
+            Direction8::NorthWest => p.up().left(),
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(d: Direction) -> Self {
+        match d {
+            Direction::North => Direction8::North,
+            Direction::East => Direction8::East,
+            Direction::South => Direction8::South,
+            Direction::West => Direction8::West,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turning() {
+        for &d in Direction::ALL.iter() {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.turn_right().turn_right(), d.reverse());
+            assert_eq!(d.delta::<i32>() + d.reverse().delta(), Vec2::zero());
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+
+        let d = Direction8::from(Direction::West);
+        assert_eq!(d.turn_right(), Direction8::NorthWest);
+        assert_eq!(d.turn_left().turn_left(), Direction8::South);
+        assert_eq!(Direction8::NorthEast.delta::<i32>(), Vec2::new(1, -1));
+    }
+
+    #[test]
+    fn stepping() {
+        let p = Vec2::new(0usize, 3);
+        assert_eq!(Direction::South.step(p), Vec2::new(0, 4));
+        assert_eq!(Direction::West.checked_step(p), None);
+        assert_eq!(Direction::North.checked_step(p), Some(Vec2::new(0, 2)));
+    }
+
+    #[test]
+    fn both_char_styles() {
+        for &d in Direction::ALL.iter() {
+            assert_eq!(Direction::from_arrow(d.to_arrow()), Some(d));
+            assert_eq!(Direction::from_letter(d.to_letter()), Some(d));
+        }
+        assert_eq!(Direction::from_arrow('v'), Some(Direction::South));
+        assert_eq!(Direction::from_letter('v'), None);
+    }
+}
@@ -0,0 +1,242 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+// An integer type that vectors can be made of.
+pub trait Coord:
+    Copy
+    + Default
+    + Ord
+    + Hash
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs_diff(self, other: Self) -> Self;
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! coord {
+    ($($T:ty),+) => {
+        $(
+            impl Coord for $T {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs_diff(self, other: Self) -> Self {
+                    if self > other {
+                        self - other
+                    } else {
+                        other - self
+                    }
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$T>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$T>::checked_sub(self, other)
+                }
+            }
+        )+
+    };
+}
+
+coord!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! vector {
+    ($Vec:ident, $($field:ident),+) => {
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $Vec<T> {
+            $(pub $field: T),+
+        }
+
+        impl<T: Coord> $Vec<T> {
+            pub fn new($($field: T),+) -> Self {
+                $Vec { $($field),+ }
+            }
+
+            pub fn zero() -> Self {
+                $Vec { $($field: T::ZERO),+ }
+            }
+
+            // The sum of the distances along each axis.
+            pub fn manhattan(self, other: Self) -> T {
+                T::ZERO $(+ self.$field.abs_diff(other.$field))+
+            }
+
+            // The largest distance along any axis.
+            pub fn chebyshev(self, other: Self) -> T {
+                let mut max = T::ZERO;
+                $(max = max.max(self.$field.abs_diff(other.$field));)+
+                max
+            }
+
+            // The sum, if no axis overflows, e.g. below zero for unsigned vectors.
+            pub fn checked_add(self, other: Self) -> Option<Self> {
+                Some($Vec { $($field: self.$field.checked_add(other.$field)?),+ })
+            }
+
+            pub fn checked_sub(self, other: Self) -> Option<Self> {
+                Some($Vec { $($field: self.$field.checked_sub(other.$field)?),+ })
+            }
+        }
+
+        impl<T: Coord> Add for $Vec<T> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                $Vec { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl<T: Coord> Sub for $Vec<T> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                $Vec { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl<T: Coord> AddAssign for $Vec<T> {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl<T: Coord> SubAssign for $Vec<T> {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        // Scales every axis.
+        impl<T: Coord> Mul<T> for $Vec<T> {
+            type Output = Self;
+
+            fn mul(self, scale: T) -> Self {
+                $Vec { $($field: self.$field * scale),+ }
+            }
+        }
+
+        impl<T: Coord + Neg<Output = T>> Neg for $Vec<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $Vec { $($field: -self.$field),+ }
+            }
+        }
+
+        // Renders as the puzzles write them, e.g. `1,2,3`.
+        impl<T: fmt::Display> fmt::Display for $Vec<T> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let axes = [$(self.$field.to_string()),+];
+                write!(f, "{}", axes.join(","))
+            }
+        }
+    };
+}
+
+vector!(Vec2, x, y);
+// Day 23's nanobots are in 3D, and day 25's constellations in 4D.
+vector!(Vec3, x, y, z);
+vector!(Vec4, x, y, z, w);
+
+// On a map, y grows downwards, so up is towards smaller y.
+impl<T: Coord> Vec2<T> {
+    // Orders top to bottom, then left to right.
+    pub fn reading_cmp(&self, other: &Self) -> Ordering {
+        self.y.cmp(&other.y).then(self.x.cmp(&other.x))
+    }
+
+    pub fn up(self) -> Self {
+        Vec2::new(self.x, self.y - T::ONE)
+    }
+
+    pub fn down(self) -> Self {
+        Vec2::new(self.x, self.y + T::ONE)
+    }
+
+    pub fn left(self) -> Self {
+        Vec2::new(self.x - T::ONE, self.y)
+    }
+
+    pub fn right(self) -> Self {
+        Vec2::new(self.x + T::ONE, self.y)
+    }
+}
+
+impl<T> From<(T, T)> for Vec2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Vec2 { x, y }
+    }
+}
+
+impl<T> From<Vec2<T>> for (T, T) {
+    fn from(v: Vec2<T>) -> Self {
+        (v.x, v.y)
+    }
+}
+
+impl<T> From<(T, T, T)> for Vec3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Vec3 { x, y, z }
+    }
+}
+
+impl<T> From<(T, T, T, T)> for Vec4<T> {
+    fn from((x, y, z, w): (T, T, T, T)) -> Self {
+        Vec4 { x, y, z, w }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = Vec2::new(3, -4);
+        let b = Vec2::new(-1, 2);
+
+        assert_eq!(a + b, Vec2::new(2, -2));
+        assert_eq!(a - b, Vec2::new(4, -6));
+        assert_eq!(a * 2, Vec2::new(6, -8));
+        assert_eq!(-a, Vec2::new(-3, 4));
+
+        let mut c = Vec3::new(1u32, 2, 3);
+        c += Vec3::new(1, 1, 1);
+        assert_eq!(c, Vec3::new(2, 3, 4));
+        assert_eq!(c.checked_sub(Vec3::new(3, 0, 0)), None);
+    }
+
+    #[test]
+    fn distances() {
+        let a = Vec4::new(0, 0, 0, 0);
+        let b = Vec4::new(3, -1, 2, -6);
+        assert_eq!(a.manhattan(b), 12);
+        assert_eq!(a.chebyshev(b), 6);
+
+        let c = Vec3::new(5usize, 1, 9);
+        assert_eq!(c.manhattan(Vec3::new(2, 4, 9)), 6);
+    }
+
+    #[test]
+    fn reading_order_goes_by_rows() {
+        let mut points = vec![Vec2::new(0, 1), Vec2::new(2, 0), Vec2::new(1, 1)];
+        points.sort_by(Vec2::reading_cmp);
+        assert_eq!(points, [Vec2::new(2, 0), Vec2::new(0, 1), Vec2::new(1, 1)]);
+
+        let p = Vec2::new(1, 1);
+        assert_eq!(p.up(), Vec2::new(1, 0));
+        assert_eq!(p.right().down(), Vec2::new(2, 2));
+        assert_eq!(Vec3::new(1, -2, 3).to_string(), "1,-2,3");
+    }
+}
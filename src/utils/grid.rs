@@ -0,0 +1,301 @@
+use crate::error::{Error, Result};
+use crate::utils::{Input, Vec2};
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+// A cell of a grid. The origin is the top left, and y grows downwards.
+pub type Point = Vec2<usize>;
+
+// The order to visit the neighbors of a cell in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Order {
+    // Top to bottom, then left to right.
+    Reading,
+    // Clockwise, starting from straight up.
+    Clockwise,
+}
+
+type Offset = Vec2<isize>;
+
+const fn delta(x: isize, y: isize) -> Offset {
+    Vec2 { x, y }
+}
+
+const NEIGHBORS4_READING: [Offset; 4] = [delta(0, -1), delta(-1, 0), delta(1, 0), delta(0, 1)];
+const NEIGHBORS4_CLOCKWISE: [Offset; 4] = [delta(0, -1), delta(1, 0), delta(0, 1), delta(-1, 0)];
+const NEIGHBORS8_READING: [Offset; 8] = [
+    delta(-1, -1),
+    delta(0, -1),
+    delta(1, -1),
+    delta(-1, 0),
+    delta(1, 0),
+    delta(-1, 1),
+    delta(0, 1),
+    delta(1, 1),
+];
+const NEIGHBORS8_CLOCKWISE: [Offset; 8] = [
+    delta(0, -1),
+    delta(1, -1),
+    delta(1, 0),
+    delta(1, 1),
+    delta(0, 1),
+    delta(-1, 1),
+    delta(-1, 0),
+    delta(-1, -1),
+];
+
+// A rectangle of cells, stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+
+    // A grid with each cell set from its point.
+    pub fn from_fn<F: FnMut(Point) -> T>(width: usize, height: usize, f: F) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Vec2::new(x, y)))
+            .map(f)
+            .collect();
+        Grid {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    // A cell per character of each line, mapped by `from_char`.
+    // The lines have to form a non-empty rectangle.
+    pub fn parse<F>(input: &Input, mut from_char: F) -> Result<Self>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let lines = input.lines();
+        let width = lines.first().map_or(0, |l| l.chars().count());
+        if width == 0 {
+            return Err(Error::new("expected a grid"));
+        }
+
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for (idx, line) in lines.iter().enumerate() {
+            let start = cells.len();
+            for c in line.chars() {
+                let cell = from_char(c)
+                    .ok_or_else(|| input.error_at(idx, format!("invalid cell `{}`", c)))?;
+                cells.push(cell);
+            }
+            if cells.len() - start != width {
+                return Err(input.error_at(idx, format!("expected {} cells", width)));
+            }
+        }
+
+        Ok(Grid {
+            cells,
+            width,
+            height: lines.len(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x < self.width && p.y < self.height
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        if self.contains(p) {
+            Some(&self.cells[self.index_of(p)])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        if self.contains(p) {
+            let i = self.index_of(p);
+            Some(&mut self.cells[i])
+        } else {
+            None
+        }
+    }
+
+    // The point `offset` away from p, if it's on the grid.
+    pub fn offset(&self, p: Point, offset: Vec2<isize>) -> Option<Point> {
+        let x = (p.x as isize).checked_add(offset.x)?;
+        let y = (p.y as isize).checked_add(offset.y)?;
+        if x < 0 || y < 0 {
+            return None;
+        }
+        Some(Vec2::new(x as usize, y as usize)).filter(|&n| self.contains(n))
+    }
+
+    // The up to 4 points sharing an edge with p.
+    pub fn neighbors4(&self, p: Point, order: Order) -> impl Iterator<Item = Point> + '_ {
+        let offsets = match order {
+            Order::Reading => &NEIGHBORS4_READING,
+            Order::Clockwise => &NEIGHBORS4_CLOCKWISE,
+        };
+        offsets.iter().filter_map(move |&o| self.offset(p, o))
+    }
+
+    // The up to 8 points sharing an edge or a corner with p.
+    pub fn neighbors8(&self, p: Point, order: Order) -> impl Iterator<Item = Point> + '_ {
+        let offsets = match order {
+            Order::Reading => &NEIGHBORS8_READING,
+            Order::Clockwise => &NEIGHBORS8_CLOCKWISE,
+        };
+        offsets.iter().filter_map(move |&o| self.offset(p, o))
+    }
+
+    // Every point of the grid, in reading order.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Vec2::new(x, y)))
+    }
+
+    // Every cell, in reading order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    // The rows, from top to bottom.
+    pub fn rows(&self) -> std::slice::Chunks<'_, T> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} is out of bounds", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    // The columns, from left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    fn index_of(&self, p: Point) -> usize {
+        p.y * self.width + p.x
+    }
+}
+
+// An empty grid, without needing a default cell.
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Grid {
+            cells: Vec::new(),
+            width: 0,
+            height: 0,
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        // Rows past the bottom are caught by the cells' own bounds check.
+        assert!(p.x < self.width, "{:?} is out of bounds", p);
+        &self.cells[self.index_of(p)]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        assert!(p.x < self.width, "{:?} is out of bounds", p);
+        let i = self.index_of(p);
+        &mut self.cells[i]
+    }
+}
+
+// Renders a row per line, with each cell's own rendering.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: usize, y: usize) -> Point {
+        Vec2::new(x, y)
+    }
+
+    #[test]
+    fn parse_maps_each_char() {
+        let input = Input::from("#.\n.#\n..");
+        let grid = Grid::parse(&input, |c| Some(c == '#')).unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert!(grid[p(0, 0)] && grid[p(1, 1)] && !grid[p(1, 0)]);
+        assert_eq!(grid.get(p(2, 0)), None);
+
+        let error = Grid::parse(&input, |c| Some(c).filter(|&c| c == '.')).unwrap_err();
+        assert_eq!(error.line, Some(1));
+        assert_eq!(
+            Grid::parse(&Input::from("..\n."), Some).unwrap_err().line,
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn neighbors_stay_on_the_grid() {
+        let grid = Grid::new(3, 3, ());
+
+        let corner: Vec<Point> = grid.neighbors4(p(0, 0), Order::Reading).collect();
+        assert_eq!(corner, [p(1, 0), p(0, 1)]);
+
+        let reading: Vec<Point> = grid.neighbors4(p(1, 1), Order::Reading).collect();
+        assert_eq!(reading, [p(1, 0), p(0, 1), p(2, 1), p(1, 2)]);
+        let clockwise: Vec<Point> = grid.neighbors4(p(1, 1), Order::Clockwise).collect();
+        assert_eq!(clockwise, [p(1, 0), p(2, 1), p(1, 2), p(0, 1)]);
+
+        assert_eq!(grid.neighbors8(p(1, 1), Order::Reading).count(), 8);
+        assert_eq!(grid.neighbors8(p(2, 2), Order::Clockwise).count(), 3);
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = Grid::from_fn(3, 2, |p| p.x + 10 * p.y);
+
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[0, 1, 2], [10, 11, 12]]);
+        assert_eq!(grid.column(1).collect::<Vec<_>>(), [&1, &11]);
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.points().nth(4), Some(p(1, 1)));
+        assert_eq!(grid.to_string(), "012\n101112\n");
+    }
+}
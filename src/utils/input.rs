@@ -0,0 +1,182 @@
+use crate::error::{Error, LineContext, Result};
+use crate::utils::{self, Grid};
+use regex::{Captures, Regex};
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+// A day's puzzle input, with the usual ways of reading it.
+// Errors carry the line number within the whole puzzle input, even for a section of it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Input {
+    lines: Vec<String>,
+    start: usize,
+}
+
+impl Input {
+    pub fn new(lines: Vec<String>) -> Self {
+        Input { lines, start: 0 }
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    // Where this input starts in the whole puzzle input, to number the lines in errors.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    // An error on the line at `idx` of this input.
+    pub fn error_at<S: Into<String>>(&self, idx: usize, message: S) -> Error {
+        Error::at_line(self.start + idx, message)
+    }
+
+    // Splits the input on blank lines. Runs of blank lines count as a single split.
+    pub fn sections(&self) -> Vec<Input> {
+        let mut sections = Vec::new();
+        let mut section: Option<Input> = None;
+
+        for (idx, line) in self.lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                sections.extend(section.take());
+            } else {
+                section
+                    .get_or_insert_with(|| Input {
+                        lines: Vec::new(),
+                        start: self.start + idx,
+                    })
+                    .lines
+                    .push(line.clone());
+            }
+        }
+        sections.extend(section);
+
+        sections
+    }
+
+    // The only line of the input, without surrounding whitespace.
+    pub fn single_line(&self) -> Result<&str> {
+        match &self.lines[..] {
+            [line] => Ok(line.trim()),
+            _ => Err(Error::new(format!(
+                "expected a single line of input, found {}",
+                self.lines.len()
+            ))),
+        }
+    }
+
+    // Every integer in the input, in reading order.
+    pub fn ints<T: FromStr>(&self) -> Result<Vec<T>> {
+        let mut ints = Vec::new();
+        for (idx, line) in self.lines.iter().enumerate() {
+            ints.extend(utils::ints(line).line(self.start + idx)?);
+        }
+        Ok(ints)
+    }
+
+    // A cell per character. The grid has to be a non-empty rectangle.
+    pub fn grid<T: TryFrom<char>>(&self) -> Result<Grid<T>> {
+        Grid::parse(self, |c| T::try_from(c).ok())
+    }
+
+    // Matches every line against `pattern`, parsing its capture groups into `T`.
+    pub fn regex_rows<T: FromCaptures>(&self, pattern: &str) -> Result<Vec<T>> {
+        let rex = Regex::new(pattern).map_err(|e| Error::new(e.to_string()))?;
+
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                let c = rex.captures(line).ok_or_else(|| {
+                    self.error_at(idx, format!("`{}` doesn't match `{}`", line, pattern))
+                })?;
+                T::from_captures(&c).line(self.start + idx)
+            })
+            .collect()
+    }
+}
+
+impl From<&str> for Input {
+    fn from(text: &str) -> Self {
+        Input::new(text.lines().map(String::from).collect())
+    }
+}
+
+// Something that can be parsed from the capture groups of a regex, in order.
+pub trait FromCaptures: Sized {
+    fn from_captures(c: &Captures) -> Result<Self>;
+}
+
+fn capture<T: FromStr>(c: &Captures, group: usize) -> Result<T> {
+    match c.get(group) {
+        Some(m) => utils::parse(m.as_str()),
+        None => Err(Error::new(format!("missing capture group {}", group))),
+    }
+}
+
+macro_rules! from_captures_tuple {
+    ($($T:ident $group:tt),+) => {
+        impl<$($T: FromStr),+> FromCaptures for ($($T,)+) {
+            fn from_captures(c: &Captures) -> Result<Self> {
+                Ok(($(capture::<$T>(c, $group)?,)+))
+            }
+        }
+    };
+}
+
+from_captures_tuple!(A 1);
+from_captures_tuple!(A 1, B 2);
+from_captures_tuple!(A 1, B 2, C 3);
+from_captures_tuple!(A 1, B 2, C 3, D 4);
+from_captures_tuple!(A 1, B 2, C 3, D 4, E 5);
+from_captures_tuple!(A 1, B 2, C 3, D 4, E 5, F 6);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_keep_their_line_numbers() {
+        let input = Input::from("a\nb\n\n\n\nc\n");
+        let sections = input.sections();
+
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].lines(), ["a", "b"]);
+        assert_eq!(sections[1].lines(), ["c"]);
+        assert_eq!(sections[1].error_at(0, "bad").line, Some(6));
+    }
+
+    #[test]
+    fn single_line_needs_exactly_one_line() {
+        assert_eq!(Input::from(" abc \n").single_line(), Ok("abc"));
+        assert!(Input::from("a\nb").single_line().is_err());
+        assert!(Input::from("").single_line().is_err());
+    }
+
+    #[test]
+    fn ints_span_every_line() {
+        let input = Input::from("pos=<-3, 4>\nx 12");
+        assert_eq!(input.ints::<i32>(), Ok(vec![-3, 4, 12]));
+        assert_eq!(input.ints::<u8>().unwrap_err().line, Some(1));
+    }
+
+    #[test]
+    fn grid_must_be_rectangular() {
+        let grid = Input::from("ab\ncd").grid::<char>().unwrap();
+        assert_eq!(grid.row(1), ['c', 'd']);
+        assert_eq!(
+            Input::from("ab\nc").grid::<char>().unwrap_err().line,
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn regex_rows_parse_each_capture() {
+        let input = Input::from("#1 @ 2,3\n#4 @ 5,6");
+        let rows = input.regex_rows::<(u32, i64, char)>(r"^#(\d+) @ (\d+),(\d)$");
+        assert_eq!(rows, Ok(vec![(1, 2, '3'), (4, 5, '6')]));
+
+        let error = input.regex_rows::<(u8,)>(r"^#(\d+)$").unwrap_err();
+        assert_eq!(error.line, Some(1));
+    }
+}
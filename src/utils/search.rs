@@ -0,0 +1,279 @@
+use crate::utils::{Grid, Point};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+// How a breadth-first search reached a cell.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Visit {
+    pub distance: usize,
+    // The cell it was reached from, or itself for the starts.
+    pub from: Point,
+}
+
+// The distance to every cell a breadth-first search reached from the nearest start.
+#[derive(Clone, Debug)]
+pub struct Bfs {
+    visits: Grid<Option<Visit>>,
+}
+
+// Searches a grid outwards from all the starts at once.
+// Ties go to whichever start, then neighbor, comes first.
+pub fn bfs<S, N, I>(width: usize, height: usize, starts: S, neighbors: N) -> Bfs
+where
+    S: IntoIterator<Item = Point>,
+    N: FnMut(Point) -> I,
+    I: IntoIterator<Item = Point>,
+{
+    bfs_until(width, height, starts, neighbors, |_| false)
+}
+
+// Stops searching once it gets to a cell `stop` accepts.
+// By then, every cell as close as that one has been found, though not searched past.
+pub fn bfs_until<S, N, I, F>(
+    width: usize,
+    height: usize,
+    starts: S,
+    mut neighbors: N,
+    mut stop: F,
+) -> Bfs
+where
+    S: IntoIterator<Item = Point>,
+    N: FnMut(Point) -> I,
+    I: IntoIterator<Item = Point>,
+    F: FnMut(Point) -> bool,
+{
+    let mut visits = Grid::new(width, height, None);
+    let mut queue = Vec::new();
+
+    for p in starts {
+        if visits[p].is_none() {
+            visits[p] = Some(Visit {
+                distance: 0,
+                from: p,
+            });
+            queue.push(p);
+        }
+    }
+
+    // The queue only ever grows, so walk along it rather than popping.
+    let mut next = 0;
+    while next < queue.len() {
+        let p = queue[next];
+        if stop(p) {
+            break;
+        }
+        let distance = visits[p].unwrap().distance + 1;
+        for n in neighbors(p) {
+            if visits[n].is_none() {
+                visits[n] = Some(Visit { distance, from: p });
+                queue.push(n);
+            }
+        }
+        next += 1;
+    }
+
+    Bfs { visits }
+}
+
+impl Bfs {
+    pub fn visit(&self, p: Point) -> Option<Visit> {
+        self.visits[p]
+    }
+
+    pub fn distance(&self, p: Point) -> Option<usize> {
+        self.visits[p].map(|v| v.distance)
+    }
+
+    // The distances to every cell reached, in reading order.
+    pub fn distances(&self) -> impl Iterator<Item = usize> + '_ {
+        self.visits.iter().filter_map(|v| v.map(|v| v.distance))
+    }
+
+    // The path a start took to reach `p`, from the start to `p`.
+    pub fn path_to(&self, p: Point) -> Option<Vec<Point>> {
+        let mut path = vec![p];
+        let mut visit = self.visits[p]?;
+        while visit.distance > 0 {
+            path.push(visit.from);
+            visit = self.visits[visit.from].unwrap();
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    // A shortest path from `p` back to a start, taking the first neighbor that's closer at every step.
+    // With neighbors in reading order, that's the path reading order prefers.
+    pub fn path_from<N, I>(&self, p: Point, mut neighbors: N) -> Option<Vec<Point>>
+    where
+        N: FnMut(Point) -> I,
+        I: IntoIterator<Item = Point>,
+    {
+        let mut path = vec![p];
+        let mut distance = self.distance(p)?;
+        let mut p = p;
+        while distance > 0 {
+            p = neighbors(p)
+                .into_iter()
+                .find(|&n| self.distance(n) == Some(distance - 1))
+                .unwrap();
+            path.push(p);
+            distance -= 1;
+        }
+        Some(path)
+    }
+}
+
+// The cheapest path from the start to a goal, with its cost.
+// `neighbors` gives each state reachable from another with the cost of getting there.
+pub fn dijkstra<S, N, I, G>(start: S, neighbors: N, is_goal: G) -> Option<(usize, Vec<S>)>
+where
+    S: Clone + Hash + Eq,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+    G: FnMut(&S) -> bool,
+{
+    astar(start, neighbors, |_| 0, is_goal)
+}
+
+// Dijkstra's, guided by a heuristic that mustn't overestimate the cost left to a goal.
+pub fn astar<S, N, I, H, G>(
+    start: S,
+    mut neighbors: N,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Option<(usize, Vec<S>)>
+where
+    S: Clone + Hash + Eq,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+    H: FnMut(&S) -> usize,
+    G: FnMut(&S) -> bool,
+{
+    // Every state seen, with the cheapest cost found to it and the state it came from.
+    let mut states = vec![(start.clone(), 0, usize::MAX)];
+    let mut indices = HashMap::new();
+    indices.insert(start.clone(), 0);
+
+    // Queues (estimated total cost, cost so far, state index), cheapest first.
+    let mut queue = BinaryHeap::new();
+    queue.push(Reverse((heuristic(&start), 0, 0)));
+
+    while let Some(Reverse((_, cost, i))) = queue.pop() {
+        if cost > states[i].1 {
+            // A cheaper way here was already expanded.
+            continue;
+        }
+        if is_goal(&states[i].0) {
+            let mut path = vec![states[i].0.clone()];
+            let mut from = states[i].2;
+            while from != usize::MAX {
+                path.push(states[from].0.clone());
+                from = states[from].2;
+            }
+            path.reverse();
+            return Some((cost, path));
+        }
+
+        for (n, step) in neighbors(&states[i].0) {
+            let n_cost = cost + step;
+            let n_index = match indices.get(&n) {
+                Some(&j) if states[j].1 <= n_cost => continue,
+                Some(&j) => {
+                    states[j].1 = n_cost;
+                    states[j].2 = i;
+                    j
+                }
+                None => {
+                    let j = states.len();
+                    indices.insert(n.clone(), j);
+                    states.push((n.clone(), n_cost, i));
+                    j
+                }
+            };
+            queue.push(Reverse((n_cost + heuristic(&n), n_cost, n_index)));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{Input, Order, Vec2};
+
+    const MAZE: [&str; 5] = ["#######", "#.....#", "#.#.#.#", "#.....#", "#######"];
+
+    fn open_neighbors(maze: &Grid<bool>) -> impl Fn(Point) -> Vec<Point> + '_ {
+        move |p| {
+            maze.neighbors4(p, Order::Reading)
+                .filter(|&n| maze[n])
+                .collect()
+        }
+    }
+
+    #[test]
+    fn bfs_from_many_starts() {
+        let maze = Grid::parse(&Input::from(MAZE.join("\n").as_str()), |c| Some(c == '.')).unwrap();
+        let starts = [Vec2::new(1, 1), Vec2::new(5, 3)];
+        let bfs = bfs(
+            maze.width(),
+            maze.height(),
+            starts.iter().copied(),
+            open_neighbors(&maze),
+        );
+
+        assert_eq!(bfs.distance(Vec2::new(3, 1)), Some(2));
+        assert_eq!(bfs.distance(Vec2::new(5, 1)), Some(2));
+        assert_eq!(bfs.distance(Vec2::new(0, 0)), None);
+        assert_eq!(bfs.distances().max(), Some(3));
+        assert_eq!(
+            bfs.path_to(Vec2::new(3, 3)),
+            Some(vec![Vec2::new(5, 3), Vec2::new(4, 3), Vec2::new(3, 3)])
+        );
+    }
+
+    #[test]
+    fn paths_prefer_reading_order() {
+        let maze = Grid::parse(&Input::from(MAZE.join("\n").as_str()), |c| Some(c == '.')).unwrap();
+        let goal = Vec2::new(3, 3);
+        let bfs = bfs(
+            maze.width(),
+            maze.height(),
+            vec![goal],
+            open_neighbors(&maze),
+        );
+
+        // Going left first and going down first are both shortest, but left comes first.
+        let path = bfs
+            .path_from(Vec2::new(5, 1), open_neighbors(&maze))
+            .unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(path[1], Vec2::new(4, 1));
+        assert_eq!(path.last(), Some(&goal));
+    }
+
+    #[test]
+    fn cheapest_path() {
+        // Walking costs 1 a step, or jump 5 steps at once for 3.
+        let steps = |&n: &usize| vec![(n + 1, 1), (n + 5, 3)];
+
+        assert_eq!(dijkstra(0, steps, |&n| n == 10), Some((6, vec![0, 5, 10])));
+        let (cost, path) = dijkstra(0, steps, |&n| n == 12).unwrap();
+        assert_eq!((cost, path.len()), (8, 5));
+
+        let (cost, _) = astar(
+            0,
+            steps,
+            |&n| 12usize.saturating_sub(n) * 3 / 5,
+            |&n| n == 12,
+        )
+        .unwrap();
+        assert_eq!(cost, 8);
+        assert_eq!(
+            dijkstra(0, steps, |&n| n == 0).map(|(cost, _)| cost),
+            Some(0)
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use AdventOfCode2018::answer::Answer;
+use AdventOfCode2018::solver::Part;
+
+use std::path::{Path, PathBuf};
+
+// Accepted answers are kept next to the inputs they belong to.
+pub fn answers_path(inputs_dir: &Path) -> PathBuf {
+    let dir = inputs_dir.parent().unwrap_or_else(|| Path::new(""));
+    dir.join("answers.toml")
+}
+
+// The accepted answer for each day and part, e.g.
+//
+//   [day1]
+//   a = 543
+//   b = 621
+//
+// Answers are compared by how they render, so coordinates are written as "x,y".
+pub struct Answers {
+    answers: BTreeMap<(u32, Part), String>,
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Answers, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        Answers::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn parse(text: &str) -> Result<Answers, String> {
+        let table: toml::value::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+        let mut answers = BTreeMap::new();
+
+        for (key, parts) in &table {
+            let day: u32 = key
+                .strip_prefix("day")
+                .and_then(|d| d.parse().ok())
+                .ok_or_else(|| format!("invalid day `{}`", key))?;
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| format!("`{}` should be a table", key))?;
+
+            for (name, value) in parts {
+                let part = match name.as_str() {
+                    "a" => Part::A,
+                    "b" => Part::B,
+                    _ => return Err(format!("invalid part `{}.{}`", key, name)),
+                };
+                let expected = match value {
+                    toml::Value::Integer(n) => n.to_string(),
+                    toml::Value::String(s) => s.clone(),
+                    _ => {
+                        return Err(format!(
+                            "`{}.{}` should be an integer or a string",
+                            key, name
+                        ))
+                    }
+                };
+                answers.insert((day, part), expected);
+            }
+        }
+
+        Ok(Answers { answers })
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn check(&self, day: u32, part: Part, answer: &Answer) -> Status {
+        match self.get(day, part) {
+            None => Status::Unknown,
+            Some(expected) if expected == answer.to_string() => Status::Pass,
+            Some(_) => Status::Fail,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail,
+    // There's no accepted answer to check against.
+    Unknown,
+    // The day didn't finish in time, so there's no answer.
+    Timeout,
+    // The day failed before it could answer this part.
+    Error,
+}
+
+impl Status {
+    // A stable name for machine-readable output.
+    pub fn name(self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Fail => "fail",
+            Status::Unknown => "unknown",
+            Status::Timeout => "timeout",
+            Status::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Status::Pass => "PASS",
+            Status::Fail => "FAIL",
+            Status::Unknown => "----",
+            Status::Timeout => "TIMEOUT",
+            Status::Error => "ERROR",
+        };
+        // Pad through the formatter so the status lines up in a table.
+        f.pad(s)
+    }
+}
+
+// Squashes multi-line answers so they fit in a single table cell.
+pub fn summarize(answer: &str) -> String {
+    match answer.lines().count() {
+        0 | 1 => answer.to_string(),
+        n => format!("<{} lines>", n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_are_checked_by_how_they_render() {
+        let answers = Answers::parse("[day13]\na = \"3,4\"\n\n[day14]\nb = 20236441\n").unwrap();
+
+        assert_eq!(
+            answers.check(13, Part::A, &Answer::Coord(3, 4)),
+            Status::Pass
+        );
+        assert_eq!(answers.check(14, Part::B, &20236441.into()), Status::Pass);
+        assert_eq!(answers.check(14, Part::B, &20236442.into()), Status::Fail);
+        assert_eq!(answers.check(14, Part::B, &Answer::Unsolved), Status::Fail);
+        // Missing parts and days have nothing to check against.
+        assert_eq!(answers.check(13, Part::B, &1.into()), Status::Unknown);
+        assert_eq!(answers.check(15, Part::A, &1.into()), Status::Unknown);
+        assert_eq!(answers.get(13, Part::A), Some("3,4"));
+    }
+
+    #[test]
+    fn answers_parse_errors() {
+        let error = |text| Answers::parse(text).err().unwrap();
+        assert_eq!(error("[dayone]\na = 1"), "invalid day `dayone`");
+        assert_eq!(error("day1 = 1"), "`day1` should be a table");
+        assert_eq!(error("[day1]\nc = 1"), "invalid part `day1.c`");
+        assert_eq!(
+            error("[day1]\na = 1.5"),
+            "`day1.a` should be an integer or a string"
+        );
+        assert_eq!(
+            error("[day1]\na = [1]"),
+            "`day1.a` should be an integer or a string"
+        );
+    }
+
+    #[test]
+    fn summarize_squashes_multiple_lines() {
+        assert_eq!(summarize("543"), "543");
+        assert_eq!(summarize("#.\n.#\n##"), "<3 lines>");
+    }
+}
@@ -0,0 +1,18 @@
+use std::process::Command;
+
+// Runs every day against answers.toml, so a refactor that changes an answer fails the build.
+#[test]
+fn answers_match_accepted_answers() {
+    let output = Command::new(env!("CARGO_BIN_EXE_AdventOfCode2018"))
+        .arg("--verify")
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("failed to run AdventOfCode2018");
+
+    assert!(
+        output.status.success(),
+        "verification failed:\n{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}